@@ -0,0 +1,176 @@
+//! Statistical constant-time tests in the style of dudect
+//! (Reparaz, Balasch, Verbauwhede: "Dude, is my code constant time?").
+//!
+//! Every benchmark times a function on inputs of two classes in random order
+//! and compares the two timing distributions with Welch's t-test.
+//! A maximum |t| above 5 indicates that the runtime depends on the class, i.e. leaks.
+//!
+//! ```bash
+//! $ cargo bench --bench ct_dudect --features fuzzing
+//! $ cargo bench --bench ct_dudect --features fuzzing -- cbd
+//! $ cargo bench --bench ct_dudect --features fuzzing -- --continuous kem_dec_saber
+//! ```
+
+use dudect_bencher::ctbench::{run_benches_console, BenchFn, BenchMetadata, BenchName, BenchOpts};
+use dudect_bencher::rand::{Rng, RngCore};
+use dudect_bencher::{BenchRng, Class, CtRunner};
+use rusty_saber::fuzzing::{self, N};
+use rusty_saber::kem::{crypto_kem_dec, crypto_kem_enc, crypto_kem_keypair};
+use rusty_saber::rng::AesState;
+use rusty_saber::saber::CRYPTO_BYTES;
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams};
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+const SAMPLES_KEM: usize = 10_000;
+const SAMPLES_POLY: usize = 100_000;
+
+/// Assign each of `n` samples to a random class.
+fn random_classes(rng: &mut BenchRng, n: usize) -> Vec<Class> {
+    (0..n)
+        .map(|_| {
+            if rng.gen::<bool>() {
+                Class::Left
+            } else {
+                Class::Right
+            }
+        })
+        .collect()
+}
+
+/// Decapsulation of valid ciphertexts (left) and of ciphertexts
+/// which fail the re-encryption check and trigger implicit rejection (right).
+fn kem_dec<P: SaberParams>(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let mut seed = [0u8; 48];
+    rng.fill_bytes(&mut seed);
+    let mut aes = AesState::new();
+    aes.randombytes_init(seed);
+
+    let mut pk = vec![0u8; P::PUBLICKEYBYTES];
+    let mut sk = vec![0u8; P::SECRETKEYBYTES];
+    crypto_kem_keypair::<P>(&mut pk, &mut sk, &mut aes).expect("crypto_kem_keypair failed!");
+
+    let mut inputs = Vec::with_capacity(SAMPLES_KEM);
+    let classes = random_classes(rng, SAMPLES_KEM);
+    for class in &classes {
+        let mut c = vec![0u8; P::BYTES_CCA_DEC];
+        let mut k = [0u8; CRYPTO_BYTES];
+        crypto_kem_enc::<P>(&mut c, &mut k, &pk, &mut aes).expect("crypto_kem_enc failed!");
+        if let Class::Right = class {
+            let i = rng.gen_range(0..c.len());
+            c[i] ^= 1 << rng.gen_range(0..8);
+        }
+        inputs.push(c);
+    }
+
+    for (class, c) in classes.into_iter().zip(inputs) {
+        runner.run_one(class, || {
+            let mut k = [0u8; CRYPTO_BYTES];
+            crypto_kem_dec::<P>(&mut k, &c, &sk).map(|()| k)
+        });
+    }
+}
+
+/// Sampling from all-zero coins (left) and from uniformly random coins (right).
+fn cbd<P: SaberParams>(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let classes = random_classes(rng, SAMPLES_POLY);
+    let inputs: Vec<Vec<u8>> = classes
+        .iter()
+        .map(|class| {
+            let mut buf = vec![0u8; P::POLYCOINBYTES];
+            if let Class::Right = class {
+                rng.fill_bytes(&mut buf);
+            }
+            buf
+        })
+        .collect();
+
+    for (class, buf) in classes.into_iter().zip(inputs) {
+        runner.run_one(class, || {
+            let mut s = [0u16; N];
+            fuzzing::cbd::<P>(&mut s, &buf).map(|()| s)
+        });
+    }
+}
+
+/// Multiplication of a random public polynomial with the zero polynomial (left)
+/// and with a random secret polynomial from the central binomial distribution (right).
+fn poly_mul_acc<P: SaberParams>(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let mut a = [0u16; N];
+    a.iter_mut().for_each(|x| *x = rng.gen());
+
+    let classes = random_classes(rng, SAMPLES_POLY);
+    let inputs: Vec<[u16; N]> = classes
+        .iter()
+        .map(|class| {
+            let mut s = [0u16; N];
+            if let Class::Right = class {
+                let mut buf = vec![0u8; P::POLYCOINBYTES];
+                rng.fill_bytes(&mut buf);
+                fuzzing::cbd::<P>(&mut s, &buf).expect("cbd failed!");
+            }
+            s
+        })
+        .collect();
+
+    for (class, s) in classes.into_iter().zip(inputs) {
+        runner.run_one(class, || {
+            let mut res = [0u16; N];
+            fuzzing::poly_mul_acc(&a, &s, &mut res);
+            res
+        });
+    }
+}
+
+macro_rules! benches {
+    ($($name:ident: $bench:ident::<$params:ty>),* $(,)?) => {
+        $(
+            fn $name(runner: &mut CtRunner, rng: &mut BenchRng) {
+                $bench::<$params>(runner, rng)
+            }
+        )*
+        const BENCHES: &[(&str, BenchFn)] = &[$((stringify!($name), $name)),*];
+    };
+}
+
+benches! {
+    kem_dec_lightsaber: kem_dec::<LightSaber>,
+    kem_dec_saber: kem_dec::<Saber>,
+    kem_dec_firesaber: kem_dec::<FireSaber>,
+    cbd_lightsaber: cbd::<LightSaber>,
+    cbd_saber: cbd::<Saber>,
+    cbd_firesaber: cbd::<FireSaber>,
+    poly_mul_acc_lightsaber: poly_mul_acc::<LightSaber>,
+    poly_mul_acc_saber: poly_mul_acc::<Saber>,
+    poly_mul_acc_firesaber: poly_mul_acc::<FireSaber>,
+}
+
+/// Parses the arguments itself instead of using `ctbench_main!`,
+/// because `cargo bench` passes `--bench`, which `ctbench_main!` rejects.
+fn main() -> io::Result<()> {
+    let mut opts = BenchOpts::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => {}
+            "--continuous" => {
+                opts.continuous = true;
+                opts.filter = args.next();
+            }
+            "--filter" => opts.filter = args.next(),
+            "--out" => opts.file_out = args.next().map(PathBuf::from),
+            filter => opts.filter = Some(filter.to_string()),
+        }
+    }
+
+    let benches = BENCHES
+        .iter()
+        .map(|&(name, benchfn)| BenchMetadata {
+            name: BenchName(name),
+            seed: None,
+            benchfn,
+        })
+        .collect();
+    run_benches_console(opts, benches)
+}
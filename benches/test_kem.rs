@@ -1,66 +1,66 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use criterion_cycles_per_byte::CyclesPerByte;
-use rusty_saber::api::{
-    CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES,
-};
 use rusty_saber::kem::{crypto_kem_dec, crypto_kem_enc, crypto_kem_keypair};
 use rusty_saber::rng::AesState;
+use rusty_saber::saber::CRYPTO_BYTES;
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams};
 
-pub fn bench_kem(criterion: &mut Criterion<CyclesPerByte>) {
-    let mut pk = [0u8; CRYPTO_PUBLICKEYBYTES];
-    let mut sk = [0u8; CRYPTO_SECRETKEYBYTES];
-    let mut c = [0u8; CRYPTO_CIPHERTEXTBYTES];
+fn bench_variant<P: SaberParams>(criterion: &mut Criterion<CyclesPerByte>) {
+    let mut pk = vec![0u8; P::PUBLICKEYBYTES];
+    let mut sk = vec![0u8; P::SECRETKEYBYTES];
+    let mut c = vec![0u8; P::BYTES_CCA_DEC];
     let mut k_a = [0u8; CRYPTO_BYTES];
     let mut k_b = [0u8; CRYPTO_BYTES];
 
+    let mut group = criterion.benchmark_group(P::ALGNAME.to_lowercase());
+
     let mut rng = AesState::with_increasing_seed();
-    criterion.bench_function("kem", |b| {
+    group.bench_function("kem", |b| {
         b.iter(|| {
-            crypto_kem_keypair(&mut pk, &mut sk, &mut rng).expect("crypto_kem_keypair failed!");
-            crypto_kem_enc(&mut c, &mut k_a, &mut pk, &mut rng).expect("crypto_kem_enc failed!");
-            crypto_kem_dec(&mut k_b, &mut c, &mut sk).expect("crypto_kem_dec failed!");
+            crypto_kem_keypair::<P>(&mut pk, &mut sk, &mut rng)
+                .expect("crypto_kem_keypair failed!");
+            crypto_kem_enc::<P>(&mut c, &mut k_a, &pk, &mut rng).expect("crypto_kem_enc failed!");
+            crypto_kem_dec::<P>(&mut k_b, &c, &sk).expect("crypto_kem_dec failed!");
             assert_eq!(k_a, k_b);
         })
     });
-}
-
-pub fn bench_kem_keypair(criterion: &mut Criterion<CyclesPerByte>) {
-    let mut pk = [0u8; CRYPTO_PUBLICKEYBYTES];
-    let mut sk = [0u8; CRYPTO_SECRETKEYBYTES];
 
     let mut rng = AesState::with_increasing_seed();
-    criterion.bench_function("kem_kp", |b| {
+    group.bench_function("kem_kp", |b| {
         b.iter(|| {
-            crypto_kem_keypair(&mut pk, &mut sk, &mut rng).expect("crypto_kem_keypair failed!");
+            crypto_kem_keypair::<P>(&mut pk, &mut sk, &mut rng)
+                .expect("crypto_kem_keypair failed!");
         })
     });
-}
-
-pub fn bench_kem_enc(criterion: &mut Criterion<CyclesPerByte>) {
-    let mut pk = [0u8; CRYPTO_PUBLICKEYBYTES];
-    let mut c = [0u8; CRYPTO_CIPHERTEXTBYTES];
-    let mut k_a = [0u8; CRYPTO_BYTES];
 
     let mut rng = AesState::with_increasing_seed();
-    criterion.bench_function("kem_enc", |b| {
+    group.bench_function("kem_enc", |b| {
         b.iter(|| {
-            crypto_kem_enc(&mut c, &mut k_a, &mut pk, &mut rng).expect("crypto_kem_enc failed!");
+            crypto_kem_enc::<P>(&mut c, &mut k_a, &pk, &mut rng).expect("crypto_kem_enc failed!");
         })
     });
-}
-
-pub fn bench_kem_dec(criterion: &mut Criterion<CyclesPerByte>) {
-    let mut sk = [0u8; CRYPTO_SECRETKEYBYTES];
-    let mut c = [0u8; CRYPTO_CIPHERTEXTBYTES];
-    let mut k_b = [0u8; CRYPTO_BYTES];
 
-    criterion.bench_function("kem_dec", |b| {
+    group.bench_function("kem_dec", |b| {
         b.iter(|| {
-            crypto_kem_dec(&mut k_b, &mut c, &mut sk).expect("crypto_kem_dec failed!");
+            crypto_kem_dec::<P>(&mut k_b, &c, &sk).expect("crypto_kem_dec failed!");
         })
     });
+
+    group.finish();
+}
+
+pub fn bench_lightsaber(criterion: &mut Criterion<CyclesPerByte>) {
+    bench_variant::<LightSaber>(criterion);
+}
+
+pub fn bench_saber(criterion: &mut Criterion<CyclesPerByte>) {
+    bench_variant::<Saber>(criterion);
+}
+
+pub fn bench_firesaber(criterion: &mut Criterion<CyclesPerByte>) {
+    bench_variant::<FireSaber>(criterion);
 }
 
 criterion_group!(name = benches;
-    config = Criterion::default().with_measurement(CyclesPerByte); targets = bench_kem, bench_kem_keypair, bench_kem_enc, bench_kem_dec);
+    config = Criterion::default().with_measurement(CyclesPerByte); targets = bench_lightsaber, bench_saber, bench_firesaber);
 criterion_main!(benches);
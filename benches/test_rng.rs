@@ -0,0 +1,20 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rusty_saber::rng::{AesState, RNGState};
+
+pub fn bench_randombytes(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("randombytes");
+
+    for size in [32usize, 128, 1024, 16384] {
+        let mut rng = AesState::with_increasing_seed();
+        let mut buf = vec![0u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| rng.randombytes(&mut buf).expect("randombytes failed!"))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_randombytes);
+criterion_main!(benches);
@@ -1,11 +1,51 @@
-fn compile_ref_impl(l: usize) {
-    // Needed because the tests have to be executed sequentially.
-    // If we run them simultaneously, the global state of the RNG
-    // in the C ref implementation will have a synchronization issue.
-    println!("cargo:rustc-env=RUST_TEST_THREADS=1");
+/// Global symbols of the C reference implementation that depend on `SABER_L`.
+/// They are renamed to `<variant>_<symbol>` so that all three variants can be linked together.
+const SYMBOLS: &[&str] = &[
+    // pack_unpack
+    "POLT2BS",
+    "BS2POLT",
+    "POLq2BS",
+    "BS2POLq",
+    "POLp2BS",
+    "BS2POLp",
+    "POLVECq2BS",
+    "BS2POLVECq",
+    "POLVECp2BS",
+    "BS2POLVECp",
+    "BS2POLmsg",
+    "POLmsg2BS",
+    // poly, poly_mul
+    "MatrixVectorMul",
+    "InnerProd",
+    "GenMatrix",
+    "GenSecret",
+    "poly_mul_acc",
+    // fips202
+    "shake128",
+    "sha3_256",
+    "sha3_512",
+    // verify
+    "verify",
+    "cmov",
+    // cbd
+    "cbd",
+    // SABER_indcpa
+    "indcpa_kem_keypair",
+    "indcpa_kem_enc",
+    "indcpa_kem_dec",
+    // kem
+    "crypto_kem_keypair",
+    "crypto_kem_enc",
+    "crypto_kem_dec",
+];
 
-    cc::Build::new()
-        .define("SABER_L", format!("{}", l).as_str())
+fn compile_ref_impl(variant: &str, l: usize) {
+    let mut build = cc::Build::new();
+    build.define("SABER_L", format!("{}", l).as_str());
+    for symbol in SYMBOLS {
+        build.define(symbol, format!("{}_{}", variant, symbol).as_str());
+    }
+    build
         .file("src/c/pack_unpack.c")
         .file("src/c/poly.c")
         .file("src/c/fips202.c")
@@ -13,43 +53,34 @@ fn compile_ref_impl(l: usize) {
         .file("src/c/cbd.c")
         .file("src/c/SABER_indcpa.c")
         .file("src/c/kem.c")
-        .object("src/c/rng.o")
-        .compile("c_reference");
-
-    println!("cargo:rustc-link-lib=crypto");
+        .compile(&format!("c_reference_{}", variant));
 }
 
 fn main() {
-    // allow variant feature flags only mutually exclusive
-    let mut flags = [
-        cfg!(feature = "lightsaber"),
-        cfg!(feature = "saber"),
-        cfg!(feature = "firesaber"),
-    ];
-    let count = flags.iter().filter(|b| **b).count();
-    if count >= 2 {
-        panic!(
-            "Sorry, you must only specify one feature: EITHER lightsaber EITHER saber OR firesaber"
-        );
-    } else if count == 0 {
-        // default is “saber”
-        flags[1] = true;
-    }
-
-    // emit configuration flag for the rank of the module
-    let mut l = 0;
-    for (i, is_set) in flags.iter().enumerate() {
-        // cargo:rustc-cfg=SABER_L_IS_2  … must be emitted for lightsaber, etc
-        if *is_set {
-            println!("cargo:rustc-cfg=SABER_L_IS_{}", i + 2);
-            l = i + 2;
-        }
-    }
+    // proof harnesses for `cargo kani`
+    println!("cargo:rustc-check-cfg=cfg(kani)");
 
     // NOTE in current rust 1.60 the “test” configuration variable is not supported.
     //      thus, we use the feature “cref” and you *have* to set it: e.g. `cargo test --features=cref`.
     //      https://github.com/rust-lang/cargo/issues/2549
-    if cfg!(feature = "cref") {
-        compile_ref_impl(l);
+    if !cfg!(feature = "cref") {
+        return;
     }
+
+    // Needed because the tests have to be executed sequentially.
+    // If we run them simultaneously, the global state of the RNG
+    // in the C ref implementation will have a synchronization issue.
+    println!("cargo:rustc-env=RUST_TEST_THREADS=1");
+
+    // The C reference implementation fixes the variant at compile time,
+    // so it is compiled once per variant with prefixed symbols.
+    for (variant, l) in [("lightsaber", 2), ("saber", 3), ("firesaber", 4)] {
+        compile_ref_impl(variant, l);
+    }
+    // The RNG does not depend on the variant; all three share its global state.
+    cc::Build::new()
+        .object("src/c/rng.o")
+        .compile("c_reference_rng");
+
+    println!("cargo:rustc-link-lib=crypto");
 }
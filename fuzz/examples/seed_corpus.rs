@@ -0,0 +1,125 @@
+//! Builds the seed corpus of all fuzz targets from the known answer tests.
+//!
+//! ```text
+//! cargo run --example seed_corpus [<count>]
+//! ```
+//!
+//! The KAT entries (100 per variant by default) are regenerated with
+//! `rusty_saber::kat` and written to `corpus/<target>/` in the input
+//! format of `rusty_saber_fuzz::Input`. The seeds of the `differential` target
+//! consist of the variant and the number of a primitive followed by the entropy
+//! of a KAT entry, so that the KEM primitives reproduce the KAT.
+
+use rusty_saber::kat::{self, KatResponse, KAT_DEFAULT_COUNT};
+use rusty_saber::pkcs8::EncodePublicKey;
+use rusty_saber::SaberParams;
+use rusty_saber_fuzz::{with_params, Input, Variant};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+fn responses<P: SaberParams>(count: usize) -> Result<Vec<KatResponse>, Box<dyn Error>> {
+    let requests = kat::generate_requests(count)?;
+    Ok(requests
+        .iter()
+        .map(kat::respond::<P>)
+        .collect::<Result<_, _>>()?)
+}
+
+/// SubjectPublicKeyInfo DER of the public key `pk` of `variant`
+fn public_key_der(variant: Variant, pk: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    macro_rules! der {
+        ($variant:ident) => {
+            rusty_saber::$variant::PublicKey::try_from(pk)?
+                .to_public_key_der()?
+                .into_vec()
+        };
+    }
+    Ok(match variant {
+        Variant::LightSaber => der!(lightsaber),
+        Variant::Saber => der!(saber),
+        Variant::FireSaber => der!(firesaber),
+    })
+}
+
+fn write_seeds<P: SaberParams>(
+    corpus: &Path,
+    variant: Variant,
+    count: usize,
+) -> Result<(), Box<dyn Error>> {
+    for response in responses::<P>(count)? {
+        let (b, tail) = response.ct.split_at(P::POLYVECCOMPRESSEDBYTES);
+        let seeds = [
+            (
+                "kem_dec",
+                "",
+                Input::encode(variant, &response.ct, &response.sk),
+            ),
+            ("bs2polvecp", "ct", Input::encode(variant, b, &[])),
+            (
+                "bs2polvecp",
+                "pk",
+                Input::encode(variant, &response.pk[..P::POLYVECCOMPRESSEDBYTES], &[]),
+            ),
+            ("bs2polt", "", Input::encode(variant, tail, &[])),
+            (
+                "public_key",
+                "raw",
+                Input::encode(variant, &response.pk, &[]),
+            ),
+            (
+                "public_key",
+                "der",
+                Input::encode(variant, &public_key_der(variant, &response.pk)?, &[]),
+            ),
+        ];
+        for (target, kind, data) in seeds {
+            let dir = corpus.join(target);
+            fs::create_dir_all(&dir)?;
+            let mut name = format!("kat-{}-{}", variant.name(), response.count);
+            if !kind.is_empty() {
+                name = format!("{}-{}", name, kind);
+            }
+            fs::write(dir.join(name), data)?;
+        }
+    }
+    println!("{}: wrote {} entries", variant.name(), count);
+    Ok(())
+}
+
+/// Number of primitives selected by the second byte of the `differential` target
+const DIFFERENTIAL_PRIMITIVES: u8 = 14;
+
+fn write_differential_seeds(corpus: &Path, count: usize) -> Result<(), Box<dyn Error>> {
+    let dir = corpus.join("differential");
+    fs::create_dir_all(&dir)?;
+    for request in kat::generate_requests(count)? {
+        for (index, variant) in (0u8..).zip(Variant::ALL) {
+            for primitive in 0..DIFFERENTIAL_PRIMITIVES {
+                let mut data = vec![index, primitive];
+                data.extend_from_slice(&request.seed);
+                let name = format!(
+                    "kat-{}-{}-primitive-{}",
+                    variant.name(),
+                    request.count,
+                    primitive
+                );
+                fs::write(dir.join(name), data)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let count = match std::env::args().nth(1) {
+        Some(count) => count.parse()?,
+        None => KAT_DEFAULT_COUNT,
+    };
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    for variant in Variant::ALL {
+        with_params!(variant, write_seeds(&corpus, variant, count))?;
+    }
+    write_differential_seeds(&corpus, count)?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! `bs2polt` (used for the tail of ciphertexts) with arbitrary bytes (first field).
+//! Inputs of the right length decode like the reference decoder; all others are rejected.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rusty_saber::fuzzing::{bs2polt, reference_bs2polt};
+use rusty_saber::SaberParams;
+use rusty_saber_fuzz::{with_params, Input};
+
+fn check<P: SaberParams>(bytes: &[u8]) {
+    match bs2polt::<P>(bytes) {
+        Ok(data) => assert_eq!(data, reference_bs2polt::<P>(bytes)),
+        Err(_) => assert_ne!(bytes.len(), P::SCALEBYTES_KEM),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = Input::parse(data) {
+        with_params!(input.variant, check(input.first));
+    }
+});
@@ -0,0 +1,22 @@
+//! `bs2polvecp` (used for public keys and ciphertexts) with arbitrary bytes (first field).
+//! Inputs of the right length decode like the reference decoder; all others are rejected.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rusty_saber::fuzzing::{bs2polvecp, reference_bs2polvecp};
+use rusty_saber::SaberParams;
+use rusty_saber_fuzz::{with_params, Input};
+
+fn check<P: SaberParams>(bytes: &[u8]) {
+    match bs2polvecp::<P>(bytes) {
+        Ok(data) => assert_eq!(data, reference_bs2polvecp(bytes)),
+        Err(_) => assert_ne!(bytes.len(), P::POLYVECCOMPRESSEDBYTES),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = Input::parse(data) {
+        with_params!(input.variant, check(input.first));
+    }
+});
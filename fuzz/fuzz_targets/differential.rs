@@ -0,0 +1,9 @@
+//! Differential fuzzing against the C reference implementation (feature `cref`).
+//! The first byte selects the primitive, the following bytes are its arguments;
+//! any divergence between Rust and C panics with the name of the primitive.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| rusty_saber::fuzzing::differential(data));
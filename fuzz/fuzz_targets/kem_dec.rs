@@ -0,0 +1,29 @@
+//! `crypto_kem_dec` with an arbitrary ciphertext (first field) and secret key (second field).
+//! Decapsulation succeeds exactly if both have the lengths of the variant.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rusty_saber::kem::crypto_kem_dec;
+use rusty_saber::{Error, SaberParams};
+use rusty_saber_fuzz::{with_params, Input};
+
+fn check<P: SaberParams>(c: &[u8], sk: &[u8]) {
+    let mut k = [0u8; 32];
+    match crypto_kem_dec::<P>(&mut k, c, sk) {
+        Ok(()) => {
+            assert_eq!(c.len(), P::BYTES_CCA_DEC);
+            assert_eq!(sk.len(), P::SECRETKEYBYTES);
+        }
+        Err(Error::InvalidLength { .. }) => {
+            assert!(c.len() != P::BYTES_CCA_DEC || sk.len() != P::SECRETKEYBYTES)
+        }
+        Err(e) => panic!("unexpected error {:?}", e),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = Input::parse(data) {
+        with_params!(input.variant, check(input.first, input.second));
+    }
+});
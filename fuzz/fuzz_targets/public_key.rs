@@ -0,0 +1,37 @@
+//! Parsing of public keys from raw bytes and from SubjectPublicKeyInfo DER (first field).
+//! Parsed keys re-encode to the input and can be used for encapsulation.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rusty_saber::pkcs8::{DecodePublicKey, EncodePublicKey};
+use rusty_saber_fuzz::{Input, Variant};
+
+macro_rules! check {
+    ($variant:ident, $bytes:expr) => {{
+        use rusty_saber::$variant::{encapsulate_derand, PublicKey, CRYPTO_PUBLICKEYBYTES};
+
+        let bytes: &[u8] = $bytes;
+        match PublicKey::try_from(bytes) {
+            Ok(pk) => {
+                assert_eq!(pk.as_bytes()[..], *bytes);
+                encapsulate_derand(&pk, &[0u8; 32]).unwrap();
+            }
+            Err(_) => assert_ne!(bytes.len(), CRYPTO_PUBLICKEYBYTES),
+        }
+        if let Ok(pk) = PublicKey::from_public_key_der(bytes) {
+            assert_eq!(pk.to_public_key_der().unwrap().as_bytes(), bytes);
+            encapsulate_derand(&pk, &[0u8; 32]).unwrap();
+        }
+    }};
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(input) = Input::parse(data) {
+        match input.variant {
+            Variant::LightSaber => check!(lightsaber, input.first),
+            Variant::Saber => check!(saber, input.first),
+            Variant::FireSaber => check!(firesaber, input.first),
+        }
+    }
+});
@@ -0,0 +1,69 @@
+//! Input format shared by the fuzz targets and the seed corpus.
+//!
+//! ```text
+//! byte 0        variant (modulo 3: LightSaber, Saber, FireSaber)
+//! bytes 1..3    length n of the first field (u16, little endian)
+//! bytes 3..     first field (n bytes or less if the input ends) || second field
+//! ```
+//!
+//! Targets with a single argument only use the first field.
+
+pub use rusty_saber::Variant;
+
+/// Variant selected by the first byte of an input
+fn variant_from_byte(byte: u8) -> Variant {
+    Variant::ALL[usize::from(byte) % 3]
+}
+
+/// Inverse of `variant_from_byte`
+fn variant_to_byte(variant: Variant) -> u8 {
+    match variant {
+        Variant::LightSaber => 0,
+        Variant::Saber => 1,
+        Variant::FireSaber => 2,
+    }
+}
+
+/// Fuzzer input split into the variant and two byte strings
+#[derive(Clone, Copy, Debug)]
+pub struct Input<'a> {
+    pub variant: Variant,
+    pub first: &'a [u8],
+    pub second: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    /// Split `data`; returns `None` if it is shorter than the header
+    pub fn parse(data: &'a [u8]) -> Option<Input<'a>> {
+        let (header, rest) = data.split_at_checked(3)?;
+        let len = usize::from(u16::from_le_bytes([header[1], header[2]])).min(rest.len());
+        let (first, second) = rest.split_at(len);
+        Some(Input {
+            variant: variant_from_byte(header[0]),
+            first,
+            second,
+        })
+    }
+
+    /// Inverse of `parse`
+    pub fn encode(variant: Variant, first: &[u8], second: &[u8]) -> Vec<u8> {
+        let len = u16::try_from(first.len()).expect("first field too long");
+        let mut data = vec![variant_to_byte(variant)];
+        data.extend_from_slice(&len.to_le_bytes());
+        data.extend_from_slice(first);
+        data.extend_from_slice(second);
+        data
+    }
+}
+
+/// Call the generic function `$f::<P>($args)` with the parameter set `P` of `$variant`
+#[macro_export]
+macro_rules! with_params {
+    ($variant:expr, $f:ident($($args:expr),*)) => {
+        match $variant {
+            $crate::Variant::LightSaber => $f::<rusty_saber::LightSaber>($($args),*),
+            $crate::Variant::Saber => $f::<rusty_saber::Saber>($($args),*),
+            $crate::Variant::FireSaber => $f::<rusty_saber::FireSaber>($($args),*),
+        }
+    };
+}
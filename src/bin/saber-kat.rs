@@ -0,0 +1,169 @@
+//! Generates and verifies the known answer test files of the NIST PQC framework.
+//!
+//! ```text
+//! saber-kat generate [--variant lightsaber|saber|firesaber] [--count 100] [--dir .]
+//! saber-kat verify <file.rsp> [--variant lightsaber|saber|firesaber]
+//! ```
+//!
+//! `generate` writes `PQCkemKAT_<sk bytes>.req` and `.rsp` for the given
+//! variant or for all three variants. `verify` recomputes every entry of
+//! a `.rsp` file and reports the first mismatching count and field.
+//! Unless `--variant` is given, it is determined by the header comment
+//! or by the length of the secret keys.
+
+use rusty_saber::kat::{self, KatResponse, KAT_DEFAULT_COUNT};
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams, Variant};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::{env, process};
+
+const USAGE: &str = "usage:
+  saber-kat generate [--variant lightsaber|saber|firesaber] [--count <n>] [--dir <dir>]
+  saber-kat verify <file.rsp> [--variant lightsaber|saber|firesaber]";
+
+fn generate<P: SaberParams>(dir: &Path, count: usize) -> Result<(), Box<dyn Error>> {
+    let requests = kat::generate_requests(count)?;
+    let responses = requests
+        .iter()
+        .map(kat::respond::<P>)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let req = dir.join(kat::file_name::<P>("req"));
+    kat::write_req(BufWriter::new(File::create(&req)?), &requests)?;
+    let rsp = dir.join(kat::file_name::<P>("rsp"));
+    kat::write_rsp::<P, _>(BufWriter::new(File::create(&rsp)?), &responses)?;
+    println!(
+        "{}: wrote {} and {}",
+        P::ALGNAME,
+        req.display(),
+        rsp.display()
+    );
+    Ok(())
+}
+
+fn verify(path: &str, variant: Option<Variant>) -> Result<(), Box<dyn Error>> {
+    let file = File::open(path).map_err(|e| format!("cannot read '{}': {}", path, e))?;
+    let (algname, responses) = kat::parse_rsp(BufReader::new(file))?;
+    let variant = variant
+        .or_else(|| algname.as_deref().and_then(Variant::from_name))
+        .or_else(|| {
+            let sk_len = responses.first()?.sk.len();
+            Variant::ALL
+                .into_iter()
+                .find(|v| v.secret_key_bytes() == sk_len)
+        })
+        .ok_or("cannot determine the variant; use --variant")?;
+
+    let verify: fn(&[KatResponse]) -> Result<(), kat::KatError> = match variant {
+        Variant::LightSaber => kat::verify::<LightSaber>,
+        Variant::Saber => kat::verify::<Saber>,
+        Variant::FireSaber => kat::verify::<FireSaber>,
+    };
+    verify(&responses).map_err(|e| format!("{}: {}", path, e))?;
+    println!(
+        "{}: {} entries of {:?} verified",
+        path,
+        responses.len(),
+        variant
+    );
+    Ok(())
+}
+
+fn parse_variant(name: &str) -> Result<Variant, Box<dyn Error>> {
+    Variant::from_name(name).ok_or_else(|| format!("unknown variant '{}'", name).into())
+}
+
+/// Subcommand with its arguments
+enum Command {
+    Generate {
+        variants: Vec<Variant>,
+        count: usize,
+        dir: String,
+    },
+    Verify {
+        file: String,
+        variant: Option<Variant>,
+    },
+    Help,
+}
+
+fn parse(args: &[String]) -> Result<Command, Box<dyn Error>> {
+    let (command, args) = args.split_first().ok_or("missing subcommand")?;
+    let mut variant = None;
+    let mut count = KAT_DEFAULT_COUNT;
+    let mut dir = ".".to_string();
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value of '{}'", arg))
+        };
+        match arg.as_str() {
+            "--variant" => variant = Some(parse_variant(value()?)?),
+            "--count" => {
+                let value = value()?;
+                count = value
+                    .parse()
+                    .map_err(|_| format!("invalid count '{}'", value))?;
+            }
+            "--dir" => dir = value()?.clone(),
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg).into()),
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    match command.as_str() {
+        "generate" if files.is_empty() => Ok(Command::Generate {
+            variants: variant.map_or(Variant::ALL.to_vec(), |v| vec![v]),
+            count,
+            dir,
+        }),
+        "verify" if files.len() == 1 => Ok(Command::Verify {
+            file: files.remove(0),
+            variant,
+        }),
+        "generate" | "verify" => Err("unexpected number of files".into()),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        _ => Err(format!("unknown subcommand '{}'", command).into()),
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Generate {
+            variants,
+            count,
+            dir,
+        } => {
+            let dir = Path::new(&dir);
+            for variant in variants {
+                match variant {
+                    Variant::LightSaber => generate::<LightSaber>(dir, count)?,
+                    Variant::Saber => generate::<Saber>(dir, count)?,
+                    Variant::FireSaber => generate::<FireSaber>(dir, count)?,
+                }
+            }
+            Ok(())
+        }
+        Command::Verify { file, variant } => verify(&file, variant),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = parse(&args).unwrap_or_else(|e| {
+        eprintln!("error: {}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    if let Err(e) = run(command) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,426 @@
+//! Command-line tool generating Saber keys, encapsulating and decapsulating shared secrets.
+//!
+//! ```text
+//! saber keygen [--variant lightsaber|saber|firesaber] --out key [--format raw|hex|pem] [--force]
+//! saber encaps --pk key.pub --ct out.ct --ss out.ss [--format raw|hex|pem] [--force]
+//! saber decaps --sk key --ct in.ct [--ss out.ss] [--format raw|hex|pem] [--force]
+//! ```
+//!
+//! `keygen` writes the secret key to `key` and the public key to `key.pub`.
+//! `encaps` and `decaps` determine the variant from the given key.
+//! `decaps` prints the shared secret as hex string unless `--ss` is given.
+//! All files of one invocation use the same format (default `raw`):
+//! the bytes of `crypto_kem_*`, these bytes as hex string, or PEM.
+//! PEM keys are SubjectPublicKeyInfo (`PUBLIC KEY`) and PKCS#8 (`PRIVATE KEY`) documents.
+//! Existing files are only overwritten with `--force`. On Unix, secret keys and
+//! shared secrets are only readable by their owner (mode 0600).
+//! Buffers holding secret keys or shared secrets are wiped when dropped.
+
+use rusty_saber::kem::{crypto_kem_dec, crypto_kem_enc};
+use rusty_saber::pkcs8::der::pem::{self, LineEnding};
+use rusty_saber::pkcs8::{self, der, spki};
+use rusty_saber::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rusty_saber::rng::AesState;
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams, Variant};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::{env, process};
+use zeroize::Zeroizing;
+
+const USAGE: &str = "usage:
+  saber keygen [--variant lightsaber|saber|firesaber] --out <key> [--format raw|hex|pem] [--force]
+  saber encaps --pk <key.pub> --ct <out.ct> --ss <out.ss> [--format raw|hex|pem] [--force]
+  saber decaps --sk <key> --ct <in.ct> [--ss <out.ss>] [--format raw|hex|pem] [--force]";
+
+/// Contents of a file, which is wiped when dropped
+type Bytes = Zeroizing<Vec<u8>>;
+
+const PEM_CIPHERTEXT: &str = "SABER CIPHERTEXT";
+const PEM_SHARED_SECRET: &str = "SABER SHARED SECRET";
+
+/// Evaluate `$body` with `$module` being the module of `$variant` (e.g. `rusty_saber::saber`)
+macro_rules! with_module {
+    ($variant:expr, $module:ident => $body:expr) => {
+        match $variant {
+            Variant::LightSaber => {
+                use rusty_saber::lightsaber as $module;
+                $body
+            }
+            Variant::Saber => {
+                use rusty_saber::saber as $module;
+                $body
+            }
+            Variant::FireSaber => {
+                use rusty_saber::firesaber as $module;
+                $body
+            }
+        }
+    };
+}
+
+fn parse_variant(name: &str) -> Result<Variant, Box<dyn Error>> {
+    Variant::from_name(name).ok_or_else(|| format!("unknown variant '{}'", name).into())
+}
+
+/// Variant whose public (`secret` false) or secret keys are `len` bytes long
+fn variant_from_key_length(len: usize, secret: bool) -> Result<Variant, Box<dyn Error>> {
+    Variant::ALL
+        .into_iter()
+        .find(|v| {
+            len == if secret {
+                v.secret_key_bytes()
+            } else {
+                v.public_key_bytes()
+            }
+        })
+        .ok_or_else(|| format!("no Saber variant has keys of {} bytes", len).into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Raw,
+    Hex,
+    Pem,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Format, Box<dyn Error>> {
+        match name {
+            "raw" => Ok(Format::Raw),
+            "hex" => Ok(Format::Hex),
+            "pem" => Ok(Format::Pem),
+            _ => Err(format!("unknown format '{}'", name).into()),
+        }
+    }
+
+    /// Encode `bytes`; `label` is the PEM label of non-key data
+    fn encode(self, bytes: &[u8], label: &str) -> Result<Bytes, Box<dyn Error>> {
+        Ok(match self {
+            Format::Raw => Zeroizing::new(bytes.to_vec()),
+            Format::Hex => {
+                let mut hex = Zeroizing::new(Vec::with_capacity(2 * bytes.len() + 1));
+                hex.extend_from_slice(to_hex(bytes).as_bytes());
+                hex.push(b'\n');
+                hex
+            }
+            Format::Pem => Zeroizing::new(
+                pem::encode_string(label, LineEnding::LF, bytes)
+                    .map_err(der::Error::from)?
+                    .into_bytes(),
+            ),
+        })
+    }
+
+    /// Decode `data` written by `encode` with the same `label`
+    fn decode(self, data: &[u8], label: &str) -> Result<Bytes, Box<dyn Error>> {
+        match self {
+            Format::Raw => Ok(Zeroizing::new(data.to_vec())),
+            Format::Hex => from_hex(data),
+            Format::Pem => {
+                let (found, bytes) = pem::decode_vec(data).map_err(der::Error::from)?;
+                let bytes = Zeroizing::new(bytes);
+                if found != label {
+                    return Err(format!("expected PEM label '{}', found '{}'", label, found).into());
+                }
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> Zeroizing<String> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = Zeroizing::new(String::with_capacity(2 * bytes.len()));
+    for b in bytes {
+        hex.push(char::from(DIGITS[usize::from(b >> 4)]));
+        hex.push(char::from(DIGITS[usize::from(b & 0xf)]));
+    }
+    hex
+}
+
+/// Decode a hex string ignoring surrounding whitespace
+fn from_hex(data: &[u8]) -> Result<Bytes, Box<dyn Error>> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| "invalid hex string")?
+        .trim()
+        .as_bytes();
+    if text.len() % 2 != 0 {
+        return Err("hex string of odd length".into());
+    }
+    let digit = |c: u8| char::from(c).to_digit(16).ok_or("invalid hex string");
+    let mut bytes = Zeroizing::new(Vec::with_capacity(text.len() / 2));
+    for pair in text.chunks(2) {
+        bytes.push((digit(pair[0])? << 4 | digit(pair[1])?) as u8);
+    }
+    Ok(bytes)
+}
+
+/// Write `data` to the new file at `path`, replacing an existing file only if `force`.
+/// `secret` files are only readable by their owner.
+fn write_file(path: &str, data: &[u8], secret: bool, force: bool) -> Result<(), Box<dyn Error>> {
+    if force {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if secret {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::AlreadyExists => format!("'{}' exists; use --force to overwrite it", path),
+        _ => format!("cannot write '{}': {}", path, e),
+    })?;
+    file.write_all(data).map_err(|e| {
+        let _ = fs::remove_file(path);
+        format!("cannot write '{}': {}", path, e).into()
+    })
+}
+
+/// Encode public key `pk` of `variant`
+fn encode_public_key(variant: Variant, format: Format, pk: &[u8]) -> Result<Bytes, Box<dyn Error>> {
+    if format != Format::Pem {
+        return format.encode(pk, "");
+    }
+    with_module!(variant, m => {
+        let pk = m::PublicKey::try_from(pk)?;
+        Ok(Zeroizing::new(pk.to_public_key_pem(LineEnding::LF)?.into_bytes()))
+    })
+}
+
+/// Encode secret key `sk` of `variant`
+fn encode_secret_key(variant: Variant, format: Format, sk: &[u8]) -> Result<Bytes, Box<dyn Error>> {
+    if format != Format::Pem {
+        return format.encode(sk, "");
+    }
+    with_module!(variant, m => {
+        let sk = m::SecretKey::try_from(sk)?;
+        Ok(Zeroizing::new(sk.to_pkcs8_pem(LineEnding::LF)?.as_bytes().to_vec()))
+    })
+}
+
+/// Public key in the SubjectPublicKeyInfo PEM document `text` and its variant
+fn decode_public_key_pem(text: &str) -> Result<(Variant, Bytes), Box<dyn Error>> {
+    for variant in Variant::ALL {
+        let pk = with_module!(variant, m => {
+            m::PublicKey::from_public_key_pem(text).map(|pk| Zeroizing::new(pk.as_ref().to_vec()))
+        });
+        match pk {
+            Ok(pk) => return Ok((variant, pk)),
+            Err(spki::Error::OidUnknown { .. }) => continue,
+            Err(e) => {
+                return Err(format!("malformed {} public key: {}", variant.algname(), e).into())
+            }
+        }
+    }
+    Err("public key of unknown algorithm".into())
+}
+
+/// Secret key in the PKCS#8 PEM document `text` and its variant
+fn decode_secret_key_pem(text: &str) -> Result<(Variant, Bytes), Box<dyn Error>> {
+    for variant in Variant::ALL {
+        let sk = with_module!(variant, m => {
+            m::SecretKey::from_pkcs8_pem(text).map(|sk| Zeroizing::new(sk.as_ref().to_vec()))
+        });
+        match sk {
+            Ok(sk) => return Ok((variant, sk)),
+            Err(pkcs8::Error::PublicKey(spki::Error::OidUnknown { .. })) => continue,
+            Err(e) => {
+                return Err(format!("malformed {} secret key: {}", variant.algname(), e).into())
+            }
+        }
+    }
+    Err("secret key of unknown algorithm".into())
+}
+
+/// Read the public (`secret` false) or secret key at `path` and determine its variant
+fn read_key(path: &str, format: Format, secret: bool) -> Result<(Variant, Bytes), Box<dyn Error>> {
+    let data =
+        Zeroizing::new(fs::read(path).map_err(|e| format!("cannot read '{}': {}", path, e))?);
+    if format == Format::Pem {
+        let text = std::str::from_utf8(&data).map_err(|_| "PEM file is not UTF-8")?;
+        return if secret {
+            decode_secret_key_pem(text)
+        } else {
+            decode_public_key_pem(text)
+        };
+    }
+    let bytes = format.decode(&data, "")?;
+    Ok((variant_from_key_length(bytes.len(), secret)?, bytes))
+}
+
+/// Writes `<out>.pub` first and removes it again if the secret key cannot be written,
+/// so that a failed run leaves no unmatched key behind.
+fn keygen(out: &str, format: Format, variant: Variant, force: bool) -> Result<(), Box<dyn Error>> {
+    let mut rng = AesState::from_os_entropy()?;
+    let (pk, sk) = with_module!(variant, m => {
+        let (pk, sk) = m::keypair(&mut rng)?;
+        (pk.as_ref().to_vec(), Zeroizing::new(sk.as_ref().to_vec()))
+    });
+    let pk_path = format!("{}.pub", out);
+    write_file(
+        &pk_path,
+        &encode_public_key(variant, format, &pk)?,
+        false,
+        force,
+    )?;
+    let written =
+        encode_secret_key(variant, format, &sk).and_then(|sk| write_file(out, &sk, true, force));
+    if written.is_err() {
+        let _ = fs::remove_file(&pk_path);
+    }
+    written
+}
+
+fn encaps<P: SaberParams>(
+    pk: &[u8],
+    ct_path: &str,
+    ss_path: &str,
+    format: Format,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut rng = AesState::from_os_entropy()?;
+    let mut ct = vec![0u8; P::BYTES_CCA_DEC];
+    let mut ss = Zeroizing::new([0u8; 32]);
+    crypto_kem_enc::<P>(&mut ct, &mut ss[..], pk, &mut rng)?;
+    write_file(ct_path, &format.encode(&ct, PEM_CIPHERTEXT)?, false, force)?;
+    write_file(
+        ss_path,
+        &format.encode(&ss[..], PEM_SHARED_SECRET)?,
+        true,
+        force,
+    )
+}
+
+fn decaps<P: SaberParams>(
+    sk: &[u8],
+    ct_path: &str,
+    ss_path: Option<&str>,
+    format: Format,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let data = fs::read(ct_path).map_err(|e| format!("cannot read '{}': {}", ct_path, e))?;
+    let ct = format.decode(&data, PEM_CIPHERTEXT)?;
+    let mut ss = Zeroizing::new([0u8; 32]);
+    crypto_kem_dec::<P>(&mut ss[..], &ct, sk)?;
+    match ss_path {
+        Some(path) => write_file(
+            path,
+            &format.encode(&ss[..], PEM_SHARED_SECRET)?,
+            true,
+            force,
+        )?,
+        None => println!("{}", to_hex(&ss[..]).as_str()),
+    }
+    Ok(())
+}
+
+/// Options `--name value` and flags `--name` of a subcommand
+struct Options {
+    values: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Options, Box<dyn Error>> {
+        const FLAGS: [&str; 1] = ["force"];
+        let mut options = Options {
+            values: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| allowed.contains(name) || FLAGS.contains(name))
+                .ok_or_else(|| format!("unexpected argument '{}'", arg))?;
+            if FLAGS.contains(&name) {
+                options.flags.push(name.to_string());
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value of '{}'", arg))?;
+            options.values.push((name.to_string(), value.clone()));
+        }
+        Ok(options)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, Box<dyn Error>> {
+        self.get(name)
+            .ok_or_else(|| format!("missing option '--{}'", name).into())
+    }
+
+    fn format(&self) -> Result<Format, Box<dyn Error>> {
+        self.get("format")
+            .map_or(Ok(Format::Raw), Format::from_name)
+    }
+
+    fn force(&self) -> bool {
+        self.flags.iter().any(|f| f == "force")
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (command, args) = args.split_first().ok_or("missing subcommand")?;
+    match command.as_str() {
+        "keygen" => {
+            let options = Options::parse(args, &["variant", "out", "format"])?;
+            let variant = options
+                .get("variant")
+                .map_or(Ok(Variant::Saber), parse_variant)?;
+            let (out, format) = (options.required("out")?, options.format()?);
+            keygen(out, format, variant, options.force())
+        }
+        "encaps" => {
+            let options = Options::parse(args, &["pk", "ct", "ss", "format"])?;
+            let (format, force) = (options.format()?, options.force());
+            let (variant, pk) = read_key(options.required("pk")?, format, false)?;
+            let (ct, ss) = (options.required("ct")?, options.required("ss")?);
+            match variant {
+                Variant::LightSaber => encaps::<LightSaber>(&pk, ct, ss, format, force),
+                Variant::Saber => encaps::<Saber>(&pk, ct, ss, format, force),
+                Variant::FireSaber => encaps::<FireSaber>(&pk, ct, ss, format, force),
+            }
+        }
+        "decaps" => {
+            let options = Options::parse(args, &["sk", "ct", "ss", "format"])?;
+            let (format, force) = (options.format()?, options.force());
+            let (variant, sk) = read_key(options.required("sk")?, format, true)?;
+            let (ct, ss) = (options.required("ct")?, options.get("ss"));
+            match variant {
+                Variant::LightSaber => decaps::<LightSaber>(&sk, ct, ss, format, force),
+                Variant::Saber => decaps::<Saber>(&sk, ct, ss, format, force),
+                Variant::FireSaber => decaps::<FireSaber>(&sk, ct, ss, format, force),
+            }
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown subcommand '{}'", command).into()),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}\n{}", e, USAGE);
+        process::exit(1);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::saber_params::{SABER_N, SABER_POLYCOINBYTES, U16};
+use crate::saber_params::{SaberParams, SABER_N, U16};
+use crate::wipe::wipe;
 use crate::U16;
-use std::num::Wrapping;
+use core::num::Wrapping;
 
 /// Interprets up to 8 bytes `x` as integer in little endian format.
 /// Returns the integer represented in the bytes as u64.
@@ -19,10 +20,12 @@ fn load_littleendian(x: &mut [u8]) -> u64 {
 /// Considers `buf` to be pseudo-random bytes and uses the
 /// difference of integers to compute a central binomially distributed
 /// value. How many bytes are used to generate one cbd-u16 value depends on
-/// the parameter set. But obviously `SABER_POLYCOINBYTES` bytes are transformed
+/// the parameter set `P`. But obviously `P::POLYCOINBYTES` bytes are transformed
 /// into `SABER_N` cbd-u16 values ase `s` is the result of this operation.
-pub(crate) fn cbd(s: &mut [U16; SABER_N], buf: [u8; SABER_POLYCOINBYTES]) {
-    if cfg!(SABER_L_IS_2) {
+pub(crate) fn cbd<P: SaberParams>(s: &mut [U16; SABER_N], buf: &[u8]) {
+    debug_assert_eq!(buf.len(), P::POLYCOINBYTES);
+
+    if P::MU == 10 {
         let (mut t, mut d): (u64, u64);
         let (mut a, mut b) = ([0u64; 4], [0u64; 4]);
 
@@ -48,8 +51,9 @@ pub(crate) fn cbd(s: &mut [U16; SABER_N], buf: [u8; SABER_POLYCOINBYTES]) {
             s[4 * i + 1] = U16!((a[1] as i32 - b[1] as i32) as u16);
             s[4 * i + 2] = U16!((a[2] as i32 - b[2] as i32) as u16);
             s[4 * i + 3] = U16!((a[3] as i32 - b[3] as i32) as u16);
+            wipe!(x, t, d, a, b);
         }
-    } else if cfg!(SABER_L_IS_3) {
+    } else if P::MU == 8 {
         let (mut t, mut d): (u32, u32);
         let (mut a, mut b) = ([0u32; 4], [0u32; 4]);
 
@@ -75,8 +79,9 @@ pub(crate) fn cbd(s: &mut [U16; SABER_N], buf: [u8; SABER_POLYCOINBYTES]) {
             s[4 * i + 1] = U16!((a[1] as i32 - b[1] as i32) as u16);
             s[4 * i + 2] = U16!((a[2] as i32 - b[2] as i32) as u16);
             s[4 * i + 3] = U16!((a[3] as i32 - b[3] as i32) as u16);
+            wipe!(x, t, d, a, b);
         }
-    } else if cfg!(SABER_L_IS_4) {
+    } else if P::MU == 6 {
         let (mut t, mut d): (u32, u32);
         let (mut a, mut b) = ([0u32; 4], [0u32; 4]);
 
@@ -103,32 +108,81 @@ pub(crate) fn cbd(s: &mut [U16; SABER_N], buf: [u8; SABER_POLYCOINBYTES]) {
             s[4 * i + 1] = U16!((a[1] as i32 - b[1] as i32) as u16);
             s[4 * i + 2] = U16!((a[2] as i32 - b[2] as i32) as u16);
             s[4 * i + 3] = U16!((a[3] as i32 - b[3] as i32) as u16);
+            wipe!(x, t, d, a, b);
         }
     }
 }
 
 #[cfg(test)]
-mod tests {
+mod oracle_tests {
     use crate::cbd::cbd;
-    use crate::link_c_reference::cbd as cbd_c;
-    use crate::saber_params::{wrappedu162u16, SABER_N, SABER_POLYCOINBYTES};
+    use crate::oracle;
+    use crate::saber_params::{FireSaber, LightSaber, Saber, SaberParams, SABER_N};
     use crate::U16;
+    use core::num::Wrapping;
     use rand::Rng;
-    use std::num::Wrapping;
 
-    #[test]
-    fn test_cbd() {
+    fn check_cbd<P: SaberParams>() {
         let mut rng = rand::thread_rng();
-        let mut s = [0u16; SABER_N];
-        let mut buf = [0u8; SABER_POLYCOINBYTES];
-        for i in 0..SABER_POLYCOINBYTES {
-            buf[i] = rng.gen();
+        let mut buf = vec![0u8; P::POLYCOINBYTES];
+        for _ in 0..64 {
+            rng.fill(&mut buf[..]);
+            let mut s = [U16!(0); SABER_N];
+            cbd::<P>(&mut s, &buf);
+            assert_eq!(s, oracle::cbd(P::MU, &buf));
+        }
+
+        // chunks with only the first or only the second half of the bits set
+        // reach the bounds MU/2 and -MU/2
+        let half = U16!((1 << (P::MU / 2)) - 1);
+        let bound = U16!(P::MU as u16 / 2);
+        for (chunk, expected) in [(half, bound), (half << (P::MU / 2), -bound)] {
+            let buf = oracle::pack(P::MU, &[chunk; SABER_N]);
+            let mut s = [U16!(0); SABER_N];
+            cbd::<P>(&mut s, &buf);
+            assert_eq!(s, [expected; SABER_N]);
+        }
+    }
+
+    #[test]
+    fn test_cbd_lightsaber() {
+        check_cbd::<LightSaber>();
+    }
+
+    #[test]
+    fn test_cbd_saber() {
+        check_cbd::<Saber>();
+    }
+
+    #[test]
+    fn test_cbd_firesaber() {
+        check_cbd::<FireSaber>();
+    }
+}
+
+#[cfg(all(test, feature = "cref"))]
+mod tests {
+    crate::link_c_reference::for_each_variant! {
+        use crate::cbd::cbd;
+        use cref::cbd as cbd_c;
+        use cref::{CRefParams, SABER_POLYCOINBYTES};
+        use crate::saber_params::{wrappedu162u16, SABER_N};
+        use crate::U16;
+        use core::num::Wrapping;
+        use rand::Rng;
+
+        #[test]
+        fn test_cbd() {
+            let mut rng = rand::thread_rng();
+            let mut s = [0u16; SABER_N];
+            let mut buf = [0u8; SABER_POLYCOINBYTES];
+            rng.fill(&mut buf[..]);
+            unsafe { cbd_c(&mut s, &mut buf) };
+            let mut s2 = [U16!(0); SABER_N];
+            cbd::<CRefParams>(&mut s2, &buf);
+            let mut check = [0u16; SABER_N];
+            wrappedu162u16(&mut check[..], &s2[..]);
+            assert_eq!(s, check);
         }
-        unsafe { cbd_c(&mut s, &mut buf) };
-        let mut s2 = [U16!(0); SABER_N];
-        cbd(&mut s2, buf);
-        let mut check = [0u16; SABER_N];
-        wrappedu162u16(&mut check[..], &s2[..]);
-        assert_eq!(s, check);
     }
 }
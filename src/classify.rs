@@ -0,0 +1,26 @@
+//! Marking of secret and public data for the `ct-check` feature
+
+/// Marks the given byte arrays or slices as secret (undefined memory for
+/// Valgrind memcheck) if feature `ct-check` is enabled. Does nothing otherwise.
+macro_rules! poison {
+    ($($x:expr),+ $(,)?) => {
+        #[cfg(feature = "ct-check")]
+        {
+            $(crate::ct_check::poison(&$x[..]);)+
+        }
+    };
+}
+
+/// Marks the given byte arrays or slices as public (defined memory for
+/// Valgrind memcheck) if feature `ct-check` is enabled. Does nothing otherwise.
+macro_rules! declassify {
+    ($($x:expr),+ $(,)?) => {
+        #[cfg(feature = "ct-check")]
+        {
+            $(crate::ct_check::declassify(&$x[..]);)+
+        }
+    };
+}
+
+pub(crate) use declassify;
+pub(crate) use poison;
@@ -0,0 +1,85 @@
+//! Valgrind memcheck client requests for checking constant-time behaviour
+//!
+//! Enabled by the `ct-check` feature. In the style of TIMECOP, secret data
+//! (the secret key, the coins from `sha3_512` and the output of `AesState`) is
+//! marked as undefined memory. Run under `valgrind`, memcheck then reports every
+//! conditional jump and every memory access whose address depends on a secret.
+//! Public results (public keys and ciphertexts) are declassified, i.e. marked as
+//! defined again. Secret results like shared secrets stay undefined; callers must
+//! declassify them before branching on them (e.g. comparing them with `==`).
+//!
+//! Outside of Valgrind, and on targets other than x86_64 and aarch64,
+//! the client requests do nothing.
+
+const MEMCHECK_BASE: u64 = ((b'M' as u64) << 24) | ((b'C' as u64) << 16);
+const MAKE_MEM_UNDEFINED: u64 = MEMCHECK_BASE + 1;
+const MAKE_MEM_DEFINED: u64 = MEMCHECK_BASE + 2;
+const RUNNING_ON_VALGRIND: u64 = 0x1001;
+
+/// Mark `data` as secret (undefined memory)
+pub fn poison(data: &[u8]) {
+    client_request(MAKE_MEM_UNDEFINED, data.as_ptr() as u64, data.len() as u64);
+}
+
+/// Mark `data` as public (defined memory)
+pub fn declassify(data: &[u8]) {
+    client_request(MAKE_MEM_DEFINED, data.as_ptr() as u64, data.len() as u64);
+}
+
+/// Returns true if the program runs under Valgrind
+pub fn running_on_valgrind() -> bool {
+    client_request(RUNNING_ON_VALGRIND, 0, 0) != 0
+}
+
+/// Issue the client request `request` with two arguments.
+/// Valgrind recognizes the special instruction sequence of `valgrind.h`,
+/// natively it does not change any register and 0 is returned.
+#[cfg(target_arch = "x86_64")]
+fn client_request(request: u64, arg1: u64, arg2: u64) -> u64 {
+    let args = [request, arg1, arg2, 0, 0, 0];
+    let mut result = 0u64;
+    // SAFETY: the rotations of rdi add up to 128 bits and `xchg rbx, rbx`
+    // is a no-op, so no register changes outside of Valgrind
+    unsafe {
+        core::arch::asm!(
+            "rol rdi, 3",
+            "rol rdi, 13",
+            "rol rdi, 61",
+            "rol rdi, 51",
+            "xchg rbx, rbx",
+            in("rax") args.as_ptr(),
+            inout("rdx") result,
+            options(nostack),
+        );
+    }
+    result
+}
+
+/// Issue the client request `request` with two arguments.
+/// Valgrind recognizes the special instruction sequence of `valgrind.h`,
+/// natively it does not change any register and 0 is returned.
+#[cfg(target_arch = "aarch64")]
+fn client_request(request: u64, arg1: u64, arg2: u64) -> u64 {
+    let args = [request, arg1, arg2, 0, 0, 0];
+    let mut result = 0u64;
+    // SAFETY: the rotations of x12 add up to 128 bits and `orr x10, x10, x10`
+    // is a no-op, so no register changes outside of Valgrind
+    unsafe {
+        core::arch::asm!(
+            "ror x12, x12, #3",
+            "ror x12, x12, #13",
+            "ror x12, x12, #51",
+            "ror x12, x12, #61",
+            "orr x10, x10, x10",
+            in("x4") args.as_ptr(),
+            inout("x3") result,
+            options(nostack),
+        );
+    }
+    result
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn client_request(_request: u64, _arg1: u64, _arg2: u64) -> u64 {
+    0
+}
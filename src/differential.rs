@@ -0,0 +1,387 @@
+//! Differential testing of the Rust implementation against the C reference implementation.
+//!
+//! `run` interprets fuzzer input as the choice of a variant and a primitive followed
+//! by its arguments, feeds the same arguments to the Rust and the C function and
+//! panics if the results differ.
+
+use crate::fips202::{sha3_256, sha3_512, shake_128};
+use crate::kem::{crypto_kem_dec, crypto_kem_enc, crypto_kem_keypair};
+use crate::link_c_reference::randombytes_init;
+use crate::pack_unpack::*;
+use crate::poly::{gen_matrix, gen_secret, inner_prod, matrix_vector_mul};
+use crate::poly_mul::poly_mul_acc;
+use crate::rng::{AesState, RNGState};
+use crate::saber_indcpa::{indcpa_kem_dec, indcpa_kem_enc, indcpa_kem_keypair};
+use crate::saber_params::*;
+use crate::verify::{cmov, verify};
+use crate::{cbd::cbd, U16};
+use core::num::Wrapping;
+use subtle::Choice;
+
+/// Reads the arguments from fuzzer input.
+/// Once the input is exhausted, all further bytes are zero.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn byte(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        let n = N.min(self.0.len());
+        out[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        out
+    }
+
+    /// Polynomial with coefficients of `bits` bits
+    fn poly(&mut self, bits: usize) -> [u16; SABER_N] {
+        let mut a = [0u16; SABER_N];
+        for x in a.iter_mut() {
+            *x = u16::from_le_bytes(self.bytes()) & (u16::MAX >> (16 - bits));
+        }
+        a
+    }
+
+    fn polyvec<const L: usize>(&mut self, bits: usize) -> [[u16; SABER_N]; L] {
+        [(); L].map(|_| self.poly(bits))
+    }
+}
+
+fn wrap(a: [u16; SABER_N]) -> [U16; SABER_N] {
+    a.map(Wrapping)
+}
+
+fn unwrap(a: [U16; SABER_N]) -> [u16; SABER_N] {
+    a.map(|x| x.0)
+}
+
+/// Vector with the maximal rank of the Rust API
+fn wrap_vec<const L: usize>(a: &[[u16; SABER_N]; L]) -> [[U16; SABER_N]; SABER_L_MAX] {
+    let mut b = [[U16!(0); SABER_N]; SABER_L_MAX];
+    for (x, y) in b.iter_mut().zip(a) {
+        *x = wrap(*y);
+    }
+    b
+}
+
+fn unwrap_vec<const L: usize>(a: &[[U16; SABER_N]; SABER_L_MAX]) -> [[u16; SABER_N]; L] {
+    let mut b = [[0u16; SABER_N]; L];
+    for (x, y) in b.iter_mut().zip(a) {
+        *x = unwrap(*y);
+    }
+    b
+}
+
+/// C and Rust RNG seeded with the same 48 bytes of entropy
+fn rngs(input: &mut Reader) -> AesState {
+    let mut entropy = input.bytes::<48>();
+    unsafe { randombytes_init(&mut entropy, 256) };
+    let mut rng = AesState::new();
+    rng.randombytes_init(entropy);
+    rng
+}
+
+crate::link_c_reference::for_each_variant! {
+    use super::*;
+    use cref::{self as c, CRefParams as P};
+    use cref::{
+        SABER_BYTES_CCA_DEC, SABER_INDCPA_PUBLICKEYBYTES, SABER_INDCPA_SECRETKEYBYTES, SABER_L,
+        SABER_POLYCOINBYTES, SABER_POLYVECBYTES, SABER_POLYVECCOMPRESSEDBYTES,
+        SABER_PUBLICKEYBYTES, SABER_SCALEBYTES_KEM, SABER_SECRETKEYBYTES,
+    };
+
+    fn diff_poly_mul(input: &mut Reader) {
+        // the operands of poly_mul_acc are reduced modulo q
+        let (a, b, acc) = (input.poly(SABER_EQ), input.poly(SABER_EQ), input.poly(16));
+        let mut res = wrap(acc);
+        poly_mul_acc(&wrap(a), &wrap(b), &mut res);
+        let mut res_c = acc;
+        unsafe { c::poly_mul_acc(&mut a.clone(), &mut b.clone(), &mut res_c) };
+        assert_eq!(unwrap(res), res_c, "poly_mul_acc");
+    }
+
+    fn diff_cbd(input: &mut Reader) {
+        let mut buf = input.bytes::<SABER_POLYCOINBYTES>();
+        let mut s = [U16!(0); SABER_N];
+        cbd::<P>(&mut s, &buf);
+        let mut s_c = [0u16; SABER_N];
+        unsafe { c::cbd(&mut s_c, &mut buf) };
+        assert_eq!(unwrap(s), s_c, "cbd");
+    }
+
+    fn diff_polt(input: &mut Reader) {
+        let data = input.poly(16);
+        let mut bytes = [0u8; SABER_SCALEBYTES_KEM];
+        polt2bs::<P>(&mut bytes, wrap(data));
+        let mut bytes_c = [0u8; SABER_SCALEBYTES_KEM];
+        unsafe { c::POLT2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLT2BS");
+
+        let mut bytes = input.bytes::<SABER_SCALEBYTES_KEM>();
+        let mut data = [U16!(0); SABER_N];
+        bs2polt::<P>(&bytes, &mut data);
+        let mut data_c = [0u16; SABER_N];
+        unsafe { c::BS2POLT(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap(data), data_c, "BS2POLT");
+    }
+
+    fn diff_polq(input: &mut Reader) {
+        let data = input.poly(16);
+        let mut bytes = [0u8; SABER_POLYBYTES];
+        polq2bs(&mut bytes, wrap(data));
+        let mut bytes_c = [0u8; SABER_POLYBYTES];
+        unsafe { c::POLq2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLq2BS");
+
+        let mut bytes = input.bytes::<SABER_POLYBYTES>();
+        let mut data = [U16!(0); SABER_N];
+        bs2polq(bytes, &mut data);
+        let mut data_c = [0u16; SABER_N];
+        unsafe { c::BS2POLq(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap(data), data_c, "BS2POLq");
+    }
+
+    fn diff_polp(input: &mut Reader) {
+        let data = input.poly(16);
+        let mut bytes = [0u8; SABER_POLYCOMPRESSEDBYTES];
+        polp2bs(&mut bytes, wrap(data));
+        let mut bytes_c = [0u8; SABER_POLYCOMPRESSEDBYTES];
+        unsafe { c::POLp2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLp2BS");
+
+        let mut bytes = input.bytes::<SABER_POLYCOMPRESSEDBYTES>();
+        let mut data = [U16!(0); SABER_N];
+        bs2polp(bytes, &mut data);
+        let mut data_c = [0u16; SABER_N];
+        unsafe { c::BS2POLp(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap(data), data_c, "BS2POLp");
+    }
+
+    fn diff_polvecq(input: &mut Reader) {
+        let data = input.polyvec(16);
+        let mut bytes = [0u8; SABER_POLYVECBYTES];
+        polvecq2bs::<P>(&mut bytes, &wrap_vec(&data));
+        let mut bytes_c = [0u8; SABER_POLYVECBYTES];
+        unsafe { c::POLVECq2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLVECq2BS");
+
+        let mut bytes = input.bytes::<SABER_POLYVECBYTES>();
+        let mut data = [[U16!(0); SABER_N]; SABER_L_MAX];
+        bs2polvecq::<P>(&bytes, &mut data);
+        let mut data_c = [[0u16; SABER_N]; SABER_L];
+        unsafe { c::BS2POLVECq(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap_vec(&data), data_c, "BS2POLVECq");
+    }
+
+    fn diff_polvecp(input: &mut Reader) {
+        let data = input.polyvec(16);
+        let mut bytes = [0u8; SABER_POLYVECCOMPRESSEDBYTES];
+        polvecp2bs::<P>(&mut bytes, &wrap_vec(&data));
+        let mut bytes_c = [0u8; SABER_POLYVECCOMPRESSEDBYTES];
+        unsafe { c::POLVECp2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLVECp2BS");
+
+        let mut bytes = input.bytes::<SABER_POLYVECCOMPRESSEDBYTES>();
+        let mut data = [[U16!(0); SABER_N]; SABER_L_MAX];
+        bs2polvecp::<P>(&bytes, &mut data);
+        let mut data_c = [[0u16; SABER_N]; SABER_L];
+        unsafe { c::BS2POLVECp(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap_vec(&data), data_c, "BS2POLVECp");
+    }
+
+    fn diff_polmsg(input: &mut Reader) {
+        let data = input.poly(16);
+        let mut bytes = [0u8; SABER_KEYBYTES];
+        polmsg2bs(&mut bytes, &wrap(data));
+        let mut bytes_c = [0u8; SABER_KEYBYTES];
+        unsafe { c::POLmsg2BS(&mut bytes_c, &mut data.clone()) };
+        assert_eq!(bytes, bytes_c, "POLmsg2BS");
+
+        let mut bytes = input.bytes::<SABER_KEYBYTES>();
+        let mut data = [U16!(0); SABER_N];
+        bs2polmsg(&bytes, &mut data);
+        let mut data_c = [0u16; SABER_N];
+        unsafe { c::BS2POLmsg(&mut bytes, &mut data_c) };
+        assert_eq!(unwrap(data), data_c, "BS2POLmsg");
+    }
+
+    fn diff_verify_cmov(input: &mut Reader) {
+        let a = input.bytes::<SABER_BYTES_CCA_DEC>();
+        // mostly equal buffers, differing in at most one byte
+        let mut b = a;
+        let position = usize::from(u16::from_le_bytes(input.bytes()));
+        if let Some(x) = b.get_mut(position) {
+            *x ^= input.byte();
+        }
+        let equal = verify(&a, &b).unwrap();
+        let differ_c = unsafe { c::verify(&a, &b, SABER_BYTES_CCA_DEC) };
+        assert_eq!(bool::from(equal), differ_c == 0, "verify");
+
+        let (mut r, x, flag) = (
+            input.bytes::<64>(),
+            input.bytes::<SABER_SECRETKEYBYTES>(),
+            input.byte() & 1,
+        );
+        let mut r_c = r;
+        cmov(
+            &mut r[..SABER_KEYBYTES],
+            &x[..SABER_KEYBYTES],
+            Choice::from(flag),
+        )
+        .unwrap();
+        unsafe { c::cmov(&mut r_c, &x, SABER_KEYBYTES, flag) };
+        assert_eq!(r, r_c, "cmov");
+    }
+
+    fn diff_poly(input: &mut Reader) {
+        let seed_a = input.bytes::<SABER_SEEDBYTES>();
+        let mut a = [[[U16!(0); SABER_N]; SABER_L_MAX]; SABER_L_MAX];
+        gen_matrix::<P>(&mut a, seed_a);
+        let mut a_c = [[[0u16; SABER_N]; SABER_L]; SABER_L];
+        unsafe { c::GenMatrix(&mut a_c, &seed_a) };
+        for (row, row_c) in a.iter().zip(a_c.iter()) {
+            assert_eq!(unwrap_vec(row), *row_c, "GenMatrix");
+        }
+
+        let seed_s = input.bytes::<SABER_NOISE_SEEDBYTES>();
+        let mut s = [[U16!(0); SABER_N]; SABER_L_MAX];
+        gen_secret::<P>(&mut s, &seed_s);
+        let mut s_c = [[0u16; SABER_N]; SABER_L];
+        unsafe { c::GenSecret(&mut s_c, &seed_s) };
+        assert_eq!(unwrap_vec(&s), s_c, "GenSecret");
+
+        let transpose = input.byte() & 1;
+        let mut res = [[U16!(0); SABER_N]; SABER_L_MAX];
+        matrix_vector_mul::<P>(&a, &s, &mut res, transpose == 1);
+        let mut res_c = [[0u16; SABER_N]; SABER_L];
+        unsafe { c::MatrixVectorMul(&a_c, &s_c, &mut res_c, transpose.into()) };
+        assert_eq!(unwrap_vec(&res), res_c, "MatrixVectorMul");
+
+        let b = input.polyvec(SABER_EP);
+        let mut res = [U16!(0); SABER_N];
+        inner_prod::<P>(&wrap_vec(&b), &s, &mut res);
+        let mut res_c = [0u16; SABER_N];
+        unsafe { c::InnerProd(&b, &s_c, &mut res_c) };
+        assert_eq!(unwrap(res), res_c, "InnerProd");
+    }
+
+    fn diff_fips202(input: &mut Reader) {
+        let seed = input.bytes::<SABER_SEEDBYTES>();
+        let inlen = SABER_SEEDBYTES as u64;
+
+        let mut out = [0u8; SABER_L * SABER_POLYVECBYTES];
+        shake_128(&mut out, &seed);
+        let mut out_c = [0u8; SABER_L * SABER_POLYVECBYTES];
+        unsafe { c::shake128(&mut out_c, out.len() as u64, &seed, inlen) };
+        assert_eq!(out, out_c, "shake128");
+
+        let (mut out, mut out_c) = ([0u8; 32], [0u8; 32]);
+        sha3_256(&mut out, &seed);
+        unsafe { c::sha3_256(&mut out_c, &seed, inlen) };
+        assert_eq!(out, out_c, "sha3_256");
+
+        let (mut out, mut out_c) = ([0u8; 64], [0u8; 64]);
+        sha3_512(&mut out, &seed);
+        unsafe { c::sha3_512(&mut out_c, &seed, inlen) };
+        assert_eq!(out, out_c, "sha3_512");
+    }
+
+    fn diff_indcpa(input: &mut Reader) {
+        let mut rng = rngs(input);
+        let (mut seed_a, mut seed_s) = ([0u8; SABER_SEEDBYTES], [0u8; SABER_NOISE_SEEDBYTES]);
+        rng.randombytes(&mut seed_a).unwrap();
+        rng.randombytes(&mut seed_s).unwrap();
+        let mut pk = [0u8; SABER_INDCPA_PUBLICKEYBYTES];
+        let mut sk = [0u8; SABER_INDCPA_SECRETKEYBYTES];
+        indcpa_kem_keypair::<P>(&mut pk, &mut sk, &seed_a, &seed_s);
+        let mut pk_c = [0u8; SABER_INDCPA_PUBLICKEYBYTES];
+        let mut sk_c = [0u8; SABER_INDCPA_SECRETKEYBYTES];
+        unsafe { c::indcpa_kem_keypair(&mut pk_c, &mut sk_c) };
+        assert_eq!((pk, sk), (pk_c, sk_c), "indcpa_kem_keypair");
+
+        let (m, seed_sp) = (
+            input.bytes::<SABER_KEYBYTES>(),
+            input.bytes::<SABER_NOISE_SEEDBYTES>(),
+        );
+        let mut ct = [0u8; SABER_BYTES_CCA_DEC];
+        indcpa_kem_enc::<P>(&m, &seed_sp, &pk, &mut ct);
+        let mut ct_c = [0u8; SABER_BYTES_CCA_DEC];
+        unsafe { c::indcpa_kem_enc(&mut m.clone(), &mut seed_sp.clone(), &mut pk_c, &mut ct_c) };
+        assert_eq!(ct, ct_c, "indcpa_kem_enc");
+
+        // decrypt a ciphertext modified by the remaining input
+        for (x, y) in ct.iter_mut().zip(input.bytes::<SABER_BYTES_CCA_DEC>()) {
+            *x ^= y;
+        }
+        let mut m = [0u8; SABER_KEYBYTES];
+        indcpa_kem_dec::<P>(&sk, &ct, &mut m);
+        let mut m_c = [0u8; SABER_KEYBYTES];
+        unsafe { c::indcpa_kem_dec(&mut sk_c, &mut ct.clone(), &mut m_c) };
+        assert_eq!(m, m_c, "indcpa_kem_dec");
+    }
+
+    fn diff_kem(input: &mut Reader) {
+        let mut rng = rngs(input);
+        let mut pk = [0u8; SABER_PUBLICKEYBYTES];
+        let mut sk = [0u8; SABER_SECRETKEYBYTES];
+        crypto_kem_keypair::<P>(&mut pk, &mut sk, &mut rng).unwrap();
+        let mut pk_c = [0u8; SABER_PUBLICKEYBYTES];
+        let mut sk_c = [0u8; SABER_SECRETKEYBYTES];
+        unsafe { c::crypto_kem_keypair(&mut pk_c, &mut sk_c) };
+        assert_eq!((pk, sk), (pk_c, sk_c), "crypto_kem_keypair");
+
+        let mut ct = [0u8; SABER_BYTES_CCA_DEC];
+        let mut k = [0u8; SABER_KEYBYTES];
+        crypto_kem_enc::<P>(&mut ct, &mut k, &pk, &mut rng).unwrap();
+        let mut ct_c = [0u8; SABER_BYTES_CCA_DEC];
+        let mut k_c = [0u8; SABER_KEYBYTES];
+        unsafe { c::crypto_kem_enc(&mut ct_c, &mut k_c, &mut pk_c) };
+        assert_eq!((ct, k), (ct_c, k_c), "crypto_kem_enc");
+
+        // decapsulate a ciphertext modified by the remaining input, possibly with an arbitrary key
+        for (x, y) in ct.iter_mut().zip(input.bytes::<SABER_BYTES_CCA_DEC>()) {
+            *x ^= y;
+        }
+        if input.byte() & 1 == 1 {
+            sk = input.bytes();
+        }
+        crypto_kem_dec::<P>(&mut k, &ct, &sk).unwrap();
+        unsafe { c::crypto_kem_dec(&mut k_c, &mut ct.clone(), &mut sk.clone()) };
+        assert_eq!(k, k_c, "crypto_kem_dec");
+    }
+
+    /// Run the primitive selected by the next byte of `input` on the arguments
+    /// in the following bytes in Rust and in C. Panics if the results differ.
+    pub(super) fn run(input: &mut Reader) {
+        match input.byte() % 14 {
+            0 => diff_poly_mul(input),
+            1 => diff_cbd(input),
+            2 => diff_polt(input),
+            3 => diff_polq(input),
+            4 => diff_polp(input),
+            5 => diff_polvecq(input),
+            6 => diff_polvecp(input),
+            7 => diff_polmsg(input),
+            8 => diff_verify_cmov(input),
+            9 => diff_poly(input),
+            10 => diff_fips202(input),
+            11 => diff_indcpa(input),
+            _ => diff_kem(input),
+        }
+    }
+}
+
+/// Run the primitive selected by the second byte of `data` for the variant
+/// selected by the first byte (modulo 3: LightSaber, Saber, FireSaber)
+/// on the arguments in the following bytes in Rust and in C.
+/// Panics if the results differ.
+pub fn run(data: &[u8]) {
+    let mut input = Reader(data);
+    match input.byte() % 3 {
+        0 => lightsaber::run(&mut input),
+        1 => saber::run(&mut input),
+        _ => firesaber::run(&mut input),
+    }
+}
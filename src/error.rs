@@ -0,0 +1,55 @@
+use core::fmt;
+
+/// Errors returned by the public API of this crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A buffer does not have the length required by the Saber variant
+    InvalidLength { expected: usize, got: usize },
+    /// The random number generator failed to provide random bytes
+    RngFailure,
+    /// The CTR_DRBG reached its reseed interval and must be reseeded
+    ReseedRequired,
+}
+
+/// `rand_core` error codes of `Error::ReseedRequired` and all other errors
+const RESEED_REQUIRED_CODE: u32 = rand_core::Error::CUSTOM_START;
+const RNG_FAILURE_CODE: u32 = rand_core::Error::CUSTOM_START + 1;
+
+impl From<rand_core::Error> for Error {
+    fn from(err: rand_core::Error) -> Self {
+        match err.code() {
+            Some(code) if code.get() == RESEED_REQUIRED_CODE => Error::ReseedRequired,
+            _ => Error::RngFailure,
+        }
+    }
+}
+
+impl From<Error> for rand_core::Error {
+    fn from(err: Error) -> Self {
+        let code = match err {
+            Error::ReseedRequired => RESEED_REQUIRED_CODE,
+            _ => RNG_FAILURE_CODE,
+        };
+        rand_core::Error::from(core::num::NonZeroU32::new(code).unwrap())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength { expected, got } => {
+                write!(
+                    f,
+                    "invalid length: expected {} bytes, got {}",
+                    expected, got
+                )
+            }
+            Error::RngFailure => write!(f, "random number generator failure"),
+            Error::ReseedRequired => write!(f, "random number generator must be reseeded"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,3 @@
+//! FireSaber, the Saber variant in NIST security category 5
+
+crate::api::variant_api!(crate::saber_params::FireSaber);
@@ -0,0 +1,73 @@
+//! Internal routines exposed to the fuzz targets in `fuzz/` and to the
+//! constant-time benchmark `benches/ct_dudect.rs`.
+//!
+//! Enabled by the `fuzzing` feature. This module is not part of the public API
+//! and may change in any release. The decoders check the length of their input
+//! like the public API does, so that they can be called with arbitrary bytes.
+
+use crate::cbd;
+#[cfg(feature = "cref")]
+pub use crate::differential::run as differential;
+use crate::kem::check_length;
+use crate::oracle;
+use crate::pack_unpack;
+use crate::poly_mul;
+use crate::saber_params::{SaberParams, SABER_EP, SABER_L_MAX, SABER_N};
+use crate::Error;
+use crate::U16;
+use core::num::Wrapping;
+
+/// Deserialize the vector of `P::L` polynomials modulo p in `bytes`
+/// of length `P::POLYVECCOMPRESSEDBYTES` (e.g. in a public key).
+/// The coefficients are returned one polynomial after another.
+pub fn bs2polvecp<P: SaberParams>(bytes: &[u8]) -> Result<Vec<u16>, Error> {
+    check_length(bytes, P::POLYVECCOMPRESSEDBYTES)?;
+    let mut data = [[U16!(0); SABER_N]; SABER_L_MAX];
+    pack_unpack::bs2polvecp::<P>(bytes, &mut data);
+    Ok(data[..P::L].iter().flatten().map(|x| x.0).collect())
+}
+
+/// Deserialize the polynomial modulo T in `bytes` of length `P::SCALEBYTES_KEM`
+/// (the tail of a ciphertext).
+pub fn bs2polt<P: SaberParams>(bytes: &[u8]) -> Result<Vec<u16>, Error> {
+    check_length(bytes, P::SCALEBYTES_KEM)?;
+    let mut data = [U16!(0); SABER_N];
+    pack_unpack::bs2polt::<P>(bytes, &mut data);
+    Ok(data.iter().map(|x| x.0).collect())
+}
+
+/// Number of coefficients of a polynomial
+pub const N: usize = SABER_N;
+
+/// Sample the polynomial `s` from the central binomial distribution
+/// using the `P::POLYCOINBYTES` coins in `buf`.
+pub fn cbd<P: SaberParams>(s: &mut [u16; N], buf: &[u8]) -> Result<(), Error> {
+    check_length(buf, P::POLYCOINBYTES)?;
+    let mut data = [U16!(0); SABER_N];
+    cbd::cbd::<P>(&mut data, buf);
+    *s = data.map(|x| x.0);
+    Ok(())
+}
+
+/// Add the product of the polynomials `a` and `b` to `res`.
+pub fn poly_mul_acc(a: &[u16; N], b: &[u16; N], res: &mut [u16; N]) {
+    let mut acc = res.map(Wrapping);
+    poly_mul::poly_mul_acc(&a.map(Wrapping), &b.map(Wrapping), &mut acc);
+    *res = acc.map(|x| x.0);
+}
+
+/// Reference decoder for `bs2polvecp`: coefficients of `SABER_EP` bits each,
+/// least significant bit first.
+pub fn reference_bs2polvecp(bytes: &[u8]) -> Vec<u16> {
+    reference_unpack(SABER_EP, bytes)
+}
+
+/// Reference decoder for `bs2polt`: coefficients of `P::ET` bits each,
+/// least significant bit first.
+pub fn reference_bs2polt<P: SaberParams>(bytes: &[u8]) -> Vec<u16> {
+    reference_unpack(P::ET, bytes)
+}
+
+fn reference_unpack(bits: usize, bytes: &[u8]) -> Vec<u16> {
+    oracle::unpack(bits, bytes).iter().map(|x| x.0).collect()
+}
@@ -0,0 +1,400 @@
+//! Known answer tests (KAT) in the file format of the NIST PQC framework.
+//!
+//! A `.req` file lists the seeds of the DRBG, one entry per `count`.
+//! The corresponding `.rsp` file additionally contains the public key `pk`,
+//! the secret key `sk`, the ciphertext `ct` and the shared secret `ss`
+//! resulting from running `crypto_kem_keypair` and `crypto_kem_enc`
+//! with an `AesState` initialized with the seed of the entry.
+//!
+//! ```text
+//! # Saber
+//!
+//! count = 0
+//! seed = 061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1
+//! pk = …
+//! ```
+
+use crate::kem::{crypto_kem_dec, crypto_kem_enc, crypto_kem_keypair};
+use crate::rng::{AesState, RNGState};
+use crate::saber_params::{SaberParams, SABER_KEYBYTES};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// Length of the seeds in bytes
+pub const KAT_SEEDBYTES: usize = 48;
+
+/// Number of entries of the KAT files of the NIST submission
+pub const KAT_DEFAULT_COUNT: usize = 100;
+
+/// Entry of a `.req` file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KatRequest {
+    pub count: usize,
+    pub seed: [u8; KAT_SEEDBYTES],
+}
+
+/// Entry of a `.rsp` file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KatResponse {
+    pub count: usize,
+    pub seed: [u8; KAT_SEEDBYTES],
+    pub pk: Vec<u8>,
+    pub sk: Vec<u8>,
+    pub ct: Vec<u8>,
+    pub ss: Vec<u8>,
+}
+
+/// Errors of reading, computing and verifying KAT files
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KatError {
+    /// reading or writing a file failed
+    Io(io::Error),
+    /// the file is malformed at line `line` (1-based)
+    Parse { line: usize, message: String },
+    /// the computation of a response failed
+    Kem(crate::Error),
+    /// field `field` of the entry with count `count` differs from the computed value
+    Mismatch { count: usize, field: &'static str },
+}
+
+impl fmt::Display for KatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KatError::Io(e) => write!(f, "I/O error: {}", e),
+            KatError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            KatError::Kem(e) => write!(f, "KEM error: {}", e),
+            KatError::Mismatch { count, field } => {
+                write!(f, "count = {}: field '{}' does not match", count, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KatError::Io(e) => Some(e),
+            KatError::Kem(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for KatError {
+    fn from(e: io::Error) -> Self {
+        KatError::Io(e)
+    }
+}
+
+impl From<crate::Error> for KatError {
+    fn from(e: crate::Error) -> Self {
+        KatError::Kem(e)
+    }
+}
+
+/// File name `PQCkemKAT_<sk bytes>.<extension>` of the NIST framework for variant `P`
+pub fn file_name<P: SaberParams>(extension: &str) -> String {
+    format!("PQCkemKAT_{}.{}", P::SECRETKEYBYTES, extension)
+}
+
+/// The `count` requests of the NIST framework: their seeds are drawn
+/// from an `AesState` initialized with seed `[0, 1, 2, …, 47]`
+pub fn generate_requests(count: usize) -> Result<Vec<KatRequest>, KatError> {
+    let mut rng = AesState::with_increasing_seed();
+    (0..count)
+        .map(|count| {
+            let mut seed = [0u8; KAT_SEEDBYTES];
+            rng.randombytes(&mut seed)?;
+            Ok(KatRequest { count, seed })
+        })
+        .collect()
+}
+
+/// Computes the response of variant `P` to `request`.
+/// Fails with `KatError::Mismatch` of field `ss` if decapsulation does not recover the shared secret.
+pub fn respond<P: SaberParams>(request: &KatRequest) -> Result<KatResponse, KatError> {
+    let mut rng = AesState::new();
+    rng.randombytes_init(request.seed);
+
+    let (mut pk, mut sk) = (vec![0u8; P::PUBLICKEYBYTES], vec![0u8; P::SECRETKEYBYTES]);
+    let (mut ct, mut ss) = (vec![0u8; P::BYTES_CCA_DEC], vec![0u8; SABER_KEYBYTES]);
+    crypto_kem_keypair::<P>(&mut pk, &mut sk, &mut rng)?;
+    crypto_kem_enc::<P>(&mut ct, &mut ss, &pk, &mut rng)?;
+
+    let mut ss1 = vec![0u8; SABER_KEYBYTES];
+    crypto_kem_dec::<P>(&mut ss1, &ct, &sk)?;
+    if ss1 != ss {
+        return Err(KatError::Mismatch {
+            count: request.count,
+            field: "ss",
+        });
+    }
+
+    Ok(KatResponse {
+        count: request.count,
+        seed: request.seed,
+        pk,
+        sk,
+        ct,
+        ss,
+    })
+}
+
+/// Recomputes every response of variant `P` and compares it field by field.
+/// Fails with `KatError::Mismatch` naming the first differing entry and field.
+pub fn verify<P: SaberParams>(responses: &[KatResponse]) -> Result<(), KatError> {
+    for expected in responses {
+        let request = KatRequest {
+            count: expected.count,
+            seed: expected.seed,
+        };
+        let computed = respond::<P>(&request)?;
+        let fields = [
+            ("pk", &expected.pk, &computed.pk),
+            ("sk", &expected.sk, &computed.sk),
+            ("ct", &expected.ct, &computed.ct),
+            ("ss", &expected.ss, &computed.ss),
+        ];
+        if let Some((field, _, _)) = fields.iter().find(|(_, e, c)| e != c) {
+            return Err(KatError::Mismatch {
+                count: expected.count,
+                field,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Write `requests` as `.req` file
+pub fn write_req<W: Write>(mut writer: W, requests: &[KatRequest]) -> io::Result<()> {
+    for request in requests {
+        writeln!(writer, "count = {}", request.count)?;
+        write_hex(&mut writer, "seed", &request.seed)?;
+        writeln!(writer, "pk =")?;
+        writeln!(writer, "sk =")?;
+        writeln!(writer, "ct =")?;
+        writeln!(writer, "ss =")?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Write `responses` of variant `P` as `.rsp` file
+pub fn write_rsp<P: SaberParams, W: Write>(
+    mut writer: W,
+    responses: &[KatResponse],
+) -> io::Result<()> {
+    write!(writer, "# {}\n\n", P::ALGNAME)?;
+    for response in responses {
+        writeln!(writer, "count = {}", response.count)?;
+        write_hex(&mut writer, "seed", &response.seed)?;
+        write_hex(&mut writer, "pk", &response.pk)?;
+        write_hex(&mut writer, "sk", &response.sk)?;
+        write_hex(&mut writer, "ct", &response.ct)?;
+        write_hex(&mut writer, "ss", &response.ss)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Parse a `.req` file; fields other than `count` and `seed` are ignored
+pub fn parse_req<R: BufRead>(reader: R) -> Result<Vec<KatRequest>, KatError> {
+    parse_records(reader)?
+        .1
+        .iter()
+        .map(|record| {
+            Ok(KatRequest {
+                count: record.count,
+                seed: record.seed()?,
+            })
+        })
+        .collect()
+}
+
+/// Parse a `.rsp` file. Returns the algorithm name of the header
+/// comment (e.g. `Saber`), if any, together with the entries.
+pub fn parse_rsp<R: BufRead>(reader: R) -> Result<(Option<String>, Vec<KatResponse>), KatError> {
+    let (algname, records) = parse_records(reader)?;
+    let responses = records
+        .iter()
+        .map(|record| {
+            Ok(KatResponse {
+                count: record.count,
+                seed: record.seed()?,
+                pk: record.bytes("pk")?,
+                sk: record.bytes("sk")?,
+                ct: record.bytes("ct")?,
+                ss: record.bytes("ss")?,
+            })
+        })
+        .collect::<Result<_, KatError>>()?;
+    Ok((algname, responses))
+}
+
+fn write_hex<W: Write>(writer: &mut W, name: &str, bytes: &[u8]) -> io::Result<()> {
+    write!(writer, "{} = ", name)?;
+    for byte in bytes {
+        write!(writer, "{:02X}", byte)?;
+    }
+    writeln!(writer)
+}
+
+/// Entry of a KAT file: its count and its other `name = value` lines
+struct Record {
+    count: usize,
+    line: usize,
+    fields: Vec<(String, String, usize)>,
+}
+
+impl Record {
+    fn field(&self, name: &str) -> Result<(&str, usize), KatError> {
+        self.fields
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, value, line)| (value.as_str(), *line))
+            .ok_or_else(|| KatError::Parse {
+                line: self.line,
+                message: format!("entry count = {} lacks field '{}'", self.count, name),
+            })
+    }
+
+    fn bytes(&self, name: &str) -> Result<Vec<u8>, KatError> {
+        let (value, line) = self.field(name)?;
+        parse_hex(value).ok_or_else(|| KatError::Parse {
+            line,
+            message: format!("field '{}' is no hex string", name),
+        })
+    }
+
+    fn seed(&self) -> Result<[u8; KAT_SEEDBYTES], KatError> {
+        let bytes = self.bytes("seed")?;
+        <[u8; KAT_SEEDBYTES]>::try_from(&bytes[..]).map_err(|_| KatError::Parse {
+            line: self.field("seed").map_or(self.line, |(_, line)| line),
+            message: format!("seed of {} bytes, expected {}", bytes.len(), KAT_SEEDBYTES),
+        })
+    }
+}
+
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 == 1 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Split the lines of a KAT file into records starting with `count = …`.
+/// Returns the first comment as algorithm name together with the records.
+fn parse_records<R: BufRead>(reader: R) -> Result<(Option<String>, Vec<Record>), KatError> {
+    let mut algname = None;
+    let mut records: Vec<Record> = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let (line, number) = (line?, i + 1);
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            algname.get_or_insert_with(|| comment.trim().to_string());
+            continue;
+        }
+
+        let (name, value) = line.split_once('=').ok_or_else(|| KatError::Parse {
+            line: number,
+            message: format!("expected 'name = value', found '{}'", line),
+        })?;
+        let (name, value) = (name.trim(), value.trim());
+        if name == "count" {
+            let count = value.parse().map_err(|_| KatError::Parse {
+                line: number,
+                message: format!("invalid count '{}'", value),
+            })?;
+            records.push(Record {
+                count,
+                line: number,
+                fields: Vec::new(),
+            });
+        } else {
+            records
+                .last_mut()
+                .ok_or_else(|| KatError::Parse {
+                    line: number,
+                    message: format!("field '{}' before the first count", name),
+                })?
+                .fields
+                .push((name.to_string(), value.to_string(), number));
+        }
+    }
+    Ok((algname, records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LightSaber, Saber};
+
+    fn responses(count: usize) -> Vec<KatResponse> {
+        generate_requests(count)
+            .unwrap()
+            .iter()
+            .map(|request| respond::<LightSaber>(request).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_req_roundtrip() {
+        let requests = generate_requests(3).unwrap();
+        assert_eq!(requests[1].count, 1);
+        let mut file = Vec::new();
+        write_req(&mut file, &requests).unwrap();
+        let text = String::from_utf8(file.clone()).unwrap();
+        assert!(text.starts_with(
+            "count = 0\nseed = 061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1\npk =\n"
+        ));
+        assert_eq!(parse_req(&file[..]).unwrap(), requests);
+    }
+
+    #[test]
+    fn test_rsp_roundtrip() {
+        let responses = responses(2);
+        let mut file = Vec::new();
+        write_rsp::<LightSaber, _>(&mut file, &responses).unwrap();
+        let (algname, parsed) = parse_rsp(&file[..]).unwrap();
+        assert_eq!(algname.as_deref(), Some("LightSaber"));
+        assert_eq!(parsed, responses);
+        verify::<LightSaber>(&parsed).unwrap();
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let mut responses = responses(3);
+        responses[1].ct[7] ^= 1;
+        responses[2].pk[0] ^= 1;
+        match verify::<LightSaber>(&responses) {
+            Err(KatError::Mismatch { count: 1, field }) => assert_eq!(field, "ct"),
+            result => panic!("unexpected result {:?}", result),
+        }
+        match verify::<Saber>(&responses) {
+            Err(KatError::Mismatch { count: 0, field }) => assert_eq!(field, "pk"),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse_error_line = |text: &str| match parse_rsp(text.as_bytes()) {
+            Err(KatError::Parse { line, .. }) => line,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(parse_error_line("# Saber\n\nseed = 00\n"), 3);
+        assert_eq!(parse_error_line("count = x\n"), 1);
+        assert_eq!(parse_error_line("count = 0\nseed 00\n"), 2);
+        assert_eq!(parse_error_line("count = 0\nseed = 0G\n"), 2);
+        assert_eq!(parse_error_line("count = 0\nseed = 00\n"), 2);
+        let seed = format!("count = 0\nseed = {}\n", "00".repeat(48));
+        assert_eq!(parse_error_line(&seed), 1);
+    }
+}
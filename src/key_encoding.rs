@@ -0,0 +1,63 @@
+//! Encoding of keys as SubjectPublicKeyInfo (public keys) and PKCS#8 (secret keys).
+//!
+//! The algorithm identifier consists of the OID `P::OID` of the variant without
+//! parameters. The public key is the bit string of the bytes of `crypto_kem_keypair`
+//! and the private key the octet string of its secret key bytes.
+//! Decoding rejects keys of another variant with `spki::Error::OidUnknown`.
+
+use crate::saber_params::SaberParams;
+use pkcs8::spki::{self, der::asn1::BitStringRef};
+use pkcs8::{
+    AlgorithmIdentifierRef, Document, ObjectIdentifier, PrivateKeyInfo, SecretDocument,
+    SubjectPublicKeyInfoRef,
+};
+
+/// Algorithm identifier of variant `P`
+fn algorithm<P: SaberParams>() -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid: ObjectIdentifier::new_unwrap(P::OID),
+        parameters: None,
+    }
+}
+
+/// Fails with `spki::Error::OidUnknown` unless `identifier` identifies variant `P`
+fn check_algorithm<P: SaberParams>(identifier: &AlgorithmIdentifierRef<'_>) -> spki::Result<()> {
+    identifier.assert_algorithm_oid(algorithm::<P>().oid)?;
+    if identifier.parameters.is_some() {
+        return Err(spki::Error::KeyMalformed);
+    }
+    Ok(())
+}
+
+/// DER encoding of the SubjectPublicKeyInfo of public key `pk` of variant `P`
+pub(crate) fn public_key_to_der<P: SaberParams>(pk: &[u8]) -> spki::Result<Document> {
+    let spki = SubjectPublicKeyInfoRef {
+        algorithm: algorithm::<P>(),
+        subject_public_key: BitStringRef::from_bytes(pk)?,
+    };
+    Document::try_from(spki)
+}
+
+/// Public key bytes of variant `P` contained in `spki`
+pub(crate) fn public_key_from_spki<P: SaberParams, const N: usize>(
+    spki: SubjectPublicKeyInfoRef<'_>,
+) -> spki::Result<[u8; N]> {
+    check_algorithm::<P>(&spki.algorithm)?;
+    spki.subject_public_key
+        .as_bytes()
+        .and_then(|bytes| <[u8; N]>::try_from(bytes).ok())
+        .ok_or(spki::Error::KeyMalformed)
+}
+
+/// DER encoding of the PKCS#8 PrivateKeyInfo of secret key `sk` of variant `P`
+pub(crate) fn secret_key_to_der<P: SaberParams>(sk: &[u8]) -> pkcs8::Result<SecretDocument> {
+    SecretDocument::try_from(PrivateKeyInfo::new(algorithm::<P>(), sk))
+}
+
+/// Secret key bytes of variant `P` contained in `private_key_info`
+pub(crate) fn secret_key_from_pkcs8<P: SaberParams, const N: usize>(
+    private_key_info: PrivateKeyInfo<'_>,
+) -> pkcs8::Result<[u8; N]> {
+    check_algorithm::<P>(&private_key_info.algorithm)?;
+    <[u8; N]>::try_from(private_key_info.private_key).map_err(|_| pkcs8::Error::KeyMalformed)
+}
@@ -1,14 +1,44 @@
-pub mod api;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+mod api;
 mod cbd;
+mod classify;
+#[cfg(feature = "ct-check")]
+pub mod ct_check;
+mod error;
 mod fips202;
+pub mod firesaber;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+#[cfg(feature = "std")]
+pub mod kat;
 pub mod kem;
+#[cfg(feature = "pkcs8")]
+mod key_encoding;
+pub mod lightsaber;
 mod pack_unpack;
 mod poly;
 mod poly_mul;
 pub mod rng;
+pub mod saber;
 mod saber_indcpa;
 mod saber_params;
+#[cfg(feature = "serde")]
+mod serialization;
 mod verify;
+mod wipe;
+
+pub use error::Error;
+#[cfg(feature = "pkcs8")]
+pub use pkcs8;
+pub use saber_params::{FireSaber, LightSaber, Saber, SaberParams, Variant};
 
-#[cfg(test)]
+#[cfg(all(feature = "fuzzing", feature = "cref"))]
+mod differential;
+#[cfg(all(any(test, feature = "fuzzing"), feature = "cref"))]
+#[cfg_attr(not(test), allow(dead_code))]
 mod link_c_reference;
+#[cfg(any(test, feature = "fuzzing"))]
+#[cfg_attr(not(test), allow(dead_code))]
+mod oracle;
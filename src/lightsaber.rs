@@ -0,0 +1,3 @@
+//! LightSaber, the Saber variant in NIST security category 1
+
+crate::api::variant_api!(crate::saber_params::LightSaber);
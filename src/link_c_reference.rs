@@ -1,86 +1,198 @@
-use crate::saber_params::*;
+//! Bindings to the C reference implementation.
+//!
+//! `build.rs` compiles the C sources once per variant with the global symbols
+//! prefixed by the variant name. The modules `lightsaber`, `saber` and `firesaber`
+//! bind them under their original names, together with the sizes of the variant.
+//! The RNG is compiled once and shared by all variants.
 
-#[link(name = "c_reference", kind = "static")]
-//pack_unpack
-extern "C" {
-    pub(crate) fn POLT2BS(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: &mut [u16; SABER_N]);
-    pub(crate) fn BS2POLT(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: &mut [u16; SABER_N]);
-    pub(crate) fn POLq2BS(bytes: &mut [u8; SABER_POLYBYTES], data: &mut [u16; SABER_N]);
-    pub(crate) fn BS2POLq(bytes: &mut [u8; SABER_POLYBYTES], data: &mut [u16; SABER_N]);
-    pub(crate) fn POLp2BS(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [u16; SABER_N]);
-    pub(crate) fn BS2POLp(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [u16; SABER_N]);
-    pub(crate) fn POLVECq2BS(
-        bytes: &mut [u8; SABER_POLYVECBYTES],
-        data: &mut [[u16; SABER_N]; SABER_L],
-    );
-    pub(crate) fn BS2POLVECq(
-        bytes: &mut [u8; SABER_POLYVECBYTES],
-        data: &mut [[u16; SABER_N]; SABER_L],
-    );
-    pub(crate) fn POLVECp2BS(
-        bytes: &mut [u8; SABER_POLYVECCOMPRESSEDBYTES],
-        data: &mut [[u16; SABER_N]; SABER_L],
-    );
-    pub(crate) fn BS2POLVECp(
-        bytes: &mut [u8; SABER_POLYVECCOMPRESSEDBYTES],
-        data: &mut [[u16; SABER_N]; SABER_L],
-    );
-    pub(crate) fn BS2POLmsg(bytes: &mut [u8; SABER_KEYBYTES], data: &mut [u16; SABER_N]);
-    pub(crate) fn POLmsg2BS(bytes: &mut [u8; SABER_KEYBYTES], data: &mut [u16; SABER_N]);
+/// Declare the functions of an `extern "C"` block, linked to `<variant>_<name>`.
+macro_rules! extern_prefixed {
+    ($variant:ident; $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        extern "C" {
+            $(
+                #[link_name = concat!(stringify!($variant), "_", stringify!($name))]
+                pub(crate) fn $name($($arg: $ty),*) $(-> $ret)?;
+            )*
+        }
+    };
 }
 
-//poly_mul
-extern "C" {
-    pub(crate) fn poly_mul_acc(
-        a: &mut [u16; SABER_N],
-        b: &mut [u16; SABER_N],
-        res: &mut [u16; SABER_N],
-    );
-}
+macro_rules! c_reference {
+    ($variant:ident, $params:ty) => {
+        pub(crate) mod $variant {
+            pub(crate) use crate::saber_params::*;
 
-//cbd
-extern "C" {
-    pub(crate) fn cbd(s: &mut [u16; SABER_N], buf: &mut [u8; SABER_POLYCOINBYTES]);
-}
+            /// The variant this module binds
+            pub(crate) type CRefParams = $params;
 
-//verify
-extern "C" {
-    pub(crate) fn verify(
-        a: &[u8; SABER_BYTES_CCA_DEC],
-        b: &[u8; SABER_BYTES_CCA_DEC],
-        len: usize,
-    ) -> u64;
-    pub(crate) fn cmov(r: &mut [u8; 64], x: &[u8; SABER_SECRETKEYBYTES], len: usize, b: u8);
-}
+            // sizes of the variant
+            pub(crate) const SABER_L: usize = CRefParams::L;
+            pub(crate) const SABER_POLYCOINBYTES: usize = CRefParams::POLYCOINBYTES;
+            pub(crate) const SABER_POLYVECBYTES: usize = CRefParams::POLYVECBYTES;
+            pub(crate) const SABER_POLYVECCOMPRESSEDBYTES: usize =
+                CRefParams::POLYVECCOMPRESSEDBYTES;
+            pub(crate) const SABER_SCALEBYTES_KEM: usize = CRefParams::SCALEBYTES_KEM;
+            pub(crate) const SABER_INDCPA_PUBLICKEYBYTES: usize = CRefParams::INDCPA_PUBLICKEYBYTES;
+            pub(crate) const SABER_INDCPA_SECRETKEYBYTES: usize = CRefParams::INDCPA_SECRETKEYBYTES;
+            pub(crate) const SABER_PUBLICKEYBYTES: usize = CRefParams::PUBLICKEYBYTES;
+            pub(crate) const SABER_SECRETKEYBYTES: usize = CRefParams::SECRETKEYBYTES;
+            pub(crate) const SABER_BYTES_CCA_DEC: usize = CRefParams::BYTES_CCA_DEC;
 
-//poly
-extern "C" {
-    pub(crate) fn MatrixVectorMul(
-        a: &[[[u16; SABER_N]; SABER_L]; SABER_L],
-        s: &[[u16; SABER_N]; SABER_L],
-        res: &mut [[u16; SABER_N]; SABER_L],
-        transpose: i16,
-    );
-    pub(crate) fn InnerProd(
-        b: &[[u16; SABER_N]; SABER_L],
-        s: &[[u16; SABER_N]; SABER_L],
-        res: &mut [u16; SABER_N],
-    );
-    pub(crate) fn GenMatrix(a: &[[[u16; SABER_N]; SABER_L]; SABER_L], seed: &[u8; SABER_SEEDBYTES]);
-    pub(crate) fn GenSecret(s: &[[u16; SABER_N]; SABER_L], seed: &[u8; SABER_NOISE_SEEDBYTES]);
+            //pack_unpack
+            extern_prefixed! {
+                $variant;
+                fn POLT2BS(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: &mut [u16; SABER_N]);
+                fn BS2POLT(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: &mut [u16; SABER_N]);
+                fn POLq2BS(bytes: &mut [u8; SABER_POLYBYTES], data: &mut [u16; SABER_N]);
+                fn BS2POLq(bytes: &mut [u8; SABER_POLYBYTES], data: &mut [u16; SABER_N]);
+                fn POLp2BS(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [u16; SABER_N]);
+                fn BS2POLp(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [u16; SABER_N]);
+                fn POLVECq2BS(
+                    bytes: &mut [u8; SABER_POLYVECBYTES],
+                    data: &mut [[u16; SABER_N]; SABER_L],
+                );
+                fn BS2POLVECq(
+                    bytes: &mut [u8; SABER_POLYVECBYTES],
+                    data: &mut [[u16; SABER_N]; SABER_L],
+                );
+                fn POLVECp2BS(
+                    bytes: &mut [u8; SABER_POLYVECCOMPRESSEDBYTES],
+                    data: &mut [[u16; SABER_N]; SABER_L],
+                );
+                fn BS2POLVECp(
+                    bytes: &mut [u8; SABER_POLYVECCOMPRESSEDBYTES],
+                    data: &mut [[u16; SABER_N]; SABER_L],
+                );
+                fn BS2POLmsg(bytes: &mut [u8; SABER_KEYBYTES], data: &mut [u16; SABER_N]);
+                fn POLmsg2BS(bytes: &mut [u8; SABER_KEYBYTES], data: &mut [u16; SABER_N]);
+            }
+
+            //poly_mul
+            extern_prefixed! {
+                $variant;
+                fn poly_mul_acc(
+                    a: &mut [u16; SABER_N],
+                    b: &mut [u16; SABER_N],
+                    res: &mut [u16; SABER_N],
+                );
+            }
+
+            //cbd
+            extern_prefixed! {
+                $variant;
+                fn cbd(s: &mut [u16; SABER_N], buf: &mut [u8; SABER_POLYCOINBYTES]);
+            }
+
+            //verify
+            extern_prefixed! {
+                $variant;
+                fn verify(
+                    a: &[u8; SABER_BYTES_CCA_DEC],
+                    b: &[u8; SABER_BYTES_CCA_DEC],
+                    len: usize,
+                ) -> u64;
+                fn cmov(r: &mut [u8; 64], x: &[u8; SABER_SECRETKEYBYTES], len: usize, b: u8);
+            }
+
+            //poly
+            extern_prefixed! {
+                $variant;
+                fn MatrixVectorMul(
+                    a: &[[[u16; SABER_N]; SABER_L]; SABER_L],
+                    s: &[[u16; SABER_N]; SABER_L],
+                    res: &mut [[u16; SABER_N]; SABER_L],
+                    transpose: i16,
+                );
+                fn InnerProd(
+                    b: &[[u16; SABER_N]; SABER_L],
+                    s: &[[u16; SABER_N]; SABER_L],
+                    res: &mut [u16; SABER_N],
+                );
+                fn GenMatrix(
+                    a: &mut [[[u16; SABER_N]; SABER_L]; SABER_L],
+                    seed: &[u8; SABER_SEEDBYTES],
+                );
+                fn GenSecret(s: &mut [[u16; SABER_N]; SABER_L], seed: &[u8; SABER_NOISE_SEEDBYTES]);
+            }
+
+            //fips202
+            extern_prefixed! {
+                $variant;
+                fn shake128(
+                    output: &mut [u8; SABER_L * SABER_POLYVECBYTES],
+                    outlen: u64,
+                    input: &[u8; SABER_SEEDBYTES],
+                    inlen: u64,
+                );
+                fn sha3_256(output: &mut [u8; 32], input: &[u8; SABER_SEEDBYTES], inlen: u64);
+                fn sha3_512(output: &mut [u8; 64], input: &[u8; SABER_SEEDBYTES], inlen: u64);
+            }
+
+            //saber_indcpa
+            extern_prefixed! {
+                $variant;
+                fn indcpa_kem_keypair(
+                    pk: &mut [u8; SABER_INDCPA_PUBLICKEYBYTES],
+                    sk: &mut [u8; SABER_INDCPA_SECRETKEYBYTES],
+                );
+                fn indcpa_kem_enc(
+                    m: &mut [u8; SABER_KEYBYTES],
+                    seed_sp: &mut [u8; SABER_NOISE_SEEDBYTES],
+                    pk: &mut [u8; SABER_INDCPA_PUBLICKEYBYTES],
+                    ciphertext: &mut [u8; SABER_BYTES_CCA_DEC],
+                );
+                fn indcpa_kem_dec(
+                    sk: &mut [u8; SABER_INDCPA_SECRETKEYBYTES],
+                    ciphertext: &mut [u8; SABER_BYTES_CCA_DEC],
+                    m: &mut [u8; SABER_KEYBYTES],
+                );
+            }
+
+            //kem
+            extern_prefixed! {
+                $variant;
+                fn crypto_kem_keypair(
+                    pk: &mut [u8; SABER_PUBLICKEYBYTES],
+                    sk: &mut [u8; SABER_SECRETKEYBYTES],
+                );
+                fn crypto_kem_enc(
+                    c: &mut [u8; SABER_BYTES_CCA_DEC],
+                    k: &mut [u8; SABER_KEYBYTES],
+                    pk: &mut [u8; SABER_PUBLICKEYBYTES],
+                );
+                fn crypto_kem_dec(
+                    k: &mut [u8; SABER_KEYBYTES],
+                    c: &mut [u8; SABER_BYTES_CCA_DEC],
+                    sk: &mut [u8; SABER_SECRETKEYBYTES],
+                );
+            }
+        }
+    };
 }
 
-//fips202
-extern "C" {
-    pub(crate) fn shake128(
-        output: &[u8; SABER_L * SABER_POLYVECBYTES],
-        outlen: u64,
-        input: &[u8; SABER_SEEDBYTES],
-        inlen: u64,
-    );
-    pub(crate) fn sha3_256(output: &[u8; 32], input: &[u8; SABER_SEEDBYTES], inlen: u64);
-    pub(crate) fn sha3_512(output: &[u8; 64], input: &[u8; SABER_SEEDBYTES], inlen: u64);
+c_reference!(lightsaber, LightSaber);
+c_reference!(saber, Saber);
+c_reference!(firesaber, FireSaber);
+
+/// Instantiate the items once per variant, in the modules `lightsaber`, `saber`
+/// and `firesaber`. The bindings of the variant are available as `cref`.
+macro_rules! for_each_variant {
+    ($($item:item)*) => {
+        mod lightsaber {
+            use crate::link_c_reference::lightsaber as cref;
+            $($item)*
+        }
+        mod saber {
+            use crate::link_c_reference::saber as cref;
+            $($item)*
+        }
+        mod firesaber {
+            use crate::link_c_reference::firesaber as cref;
+            $($item)*
+        }
+    };
 }
+pub(crate) use for_each_variant;
 
 //rng
 extern "C" {
@@ -88,47 +200,10 @@ extern "C" {
     pub(crate) fn randombytes_init(entropy_input: &mut [u8; 48], security_strength: i32);
 }
 
-//saber_indcpa
-extern "C" {
-    pub(crate) fn indcpa_kem_keypair(
-        pk: &mut [u8; SABER_INDCPA_PUBLICKEYBYTES],
-        sk: &mut [u8; SABER_INDCPA_SECRETKEYBYTES],
-    );
-    pub(crate) fn indcpa_kem_enc(
-        m: &mut [u8; SABER_KEYBYTES],
-        seed_sp: &mut [u8; SABER_NOISE_SEEDBYTES],
-        pk: &mut [u8; SABER_INDCPA_PUBLICKEYBYTES],
-        ciphertext: &mut [u8; SABER_BYTES_CCA_DEC],
-    );
-    pub(crate) fn indcpa_kem_dec(
-        sk: &mut [u8; SABER_INDCPA_SECRETKEYBYTES],
-        ciphertext: &mut [u8; SABER_BYTES_CCA_DEC],
-        m: &mut [u8; SABER_KEYBYTES],
-    );
-}
-
-//kem
-extern "C" {
-    pub(crate) fn crypto_kem_keypair(
-        pk: &mut [u8; SABER_PUBLICKEYBYTES],
-        sk: &mut [u8; SABER_SECRETKEYBYTES],
-    );
-    pub(crate) fn crypto_kem_enc(
-        c: &mut [u8; SABER_BYTES_CCA_DEC],
-        k: &mut [u8; SABER_KEYBYTES],
-        pk: &mut [u8; SABER_PUBLICKEYBYTES],
-    );
-    pub(crate) fn crypto_kem_dec(
-        k: &mut [u8; SABER_KEYBYTES],
-        c: &mut [u8; SABER_BYTES_CCA_DEC],
-        sk: &mut [u8; SABER_SECRETKEYBYTES],
-    );
-}
-
 pub(crate) fn initialize_c_randombytes() {
     let mut entropy_inp = [0u8; 48];
-    for i in 0..48 {
-        entropy_inp[i] = i as u8;
+    for (i, e) in entropy_inp.iter_mut().enumerate() {
+        *e = i as u8;
     }
     unsafe { randombytes_init(&mut entropy_inp, 256) };
 }
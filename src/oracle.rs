@@ -0,0 +1,79 @@
+//! Straightforward reference implementations used as test oracles.
+//!
+//! They follow the specification as literally as possible and
+//! trade speed for obviousness. Unlike `link_c_reference`, they are
+//! available without the `cref` feature and cover all three variants.
+//! The fuzz targets use them through the `fuzzing` module.
+
+use crate::saber_params::{SABER_N, U16};
+use crate::U16;
+use core::num::Wrapping;
+
+/// Schoolbook multiplication in Z_{2^16}[X]/(X^N + 1).
+/// The product of `a` and `b` is added to `res`.
+pub(crate) fn poly_mul_acc(a: &[U16; SABER_N], b: &[U16; SABER_N], res: &mut [U16; SABER_N]) {
+    for i in 0..SABER_N {
+        for j in 0..SABER_N {
+            let product = a[i] * b[j];
+            if i + j < SABER_N {
+                res[i + j] += product;
+            } else {
+                // X^N = -1
+                res[i + j - SABER_N] -= product;
+            }
+        }
+    }
+}
+
+/// Central binomial distribution with parameter `mu`, one bit at a time.
+///
+/// Coefficient `i` is the number of set bits among the first `mu/2` bits
+/// of the `i`-th `mu`-bit chunk of `buf` (least significant bit first)
+/// minus the number of set bits among the last `mu/2` bits.
+pub(crate) fn cbd(mu: usize, buf: &[u8]) -> [U16; SABER_N] {
+    let bit = |k: usize| ((buf[k / 8] >> (k % 8)) & 1) as u16;
+    let mut s = [U16!(0); SABER_N];
+    for (i, coefficient) in s.iter_mut().enumerate() {
+        let chunk = i * mu;
+        let a: u16 = (chunk..chunk + mu / 2).map(bit).sum();
+        let b: u16 = (chunk + mu / 2..chunk + mu).map(bit).sum();
+        *coefficient = U16!(a) - U16!(b);
+    }
+    s
+}
+
+/// Serialize the lowest `bits` bits of each coefficient in `data`,
+/// least significant bit first, into `bits * data.len() / 8` bytes.
+pub(crate) fn pack(bits: usize, data: &[U16]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits * data.len() / 8];
+    for (i, coefficient) in data.iter().enumerate() {
+        for k in 0..bits {
+            let position = i * bits + k;
+            bytes[position / 8] |= (((coefficient.0 >> k) & 1) as u8) << (position % 8);
+        }
+    }
+    bytes
+}
+
+/// Inverse of `pack`: read `8 * bytes.len() / bits` coefficients of `bits` bits each.
+pub(crate) fn unpack(bits: usize, bytes: &[u8]) -> Vec<U16> {
+    (0..8 * bytes.len() / bits)
+        .map(|i| {
+            let coefficient = (0..bits).fold(0u16, |acc, k| {
+                let position = i * bits + k;
+                acc | ((((bytes[position / 8] >> (position % 8)) & 1) as u16) << k)
+            });
+            U16!(coefficient)
+        })
+        .collect()
+}
+
+/// Polynomial with uniformly random coefficients in `[0, 2^bits)`
+#[cfg(test)]
+pub(crate) fn random_poly(rng: &mut impl rand::Rng, bits: usize) -> [U16; SABER_N] {
+    let mut a = [U16!(0); SABER_N];
+    for coefficient in a.iter_mut() {
+        *coefficient = U16!(rng.gen::<u16>() >> (16 - bits));
+    }
+    a
+}
@@ -1,16 +1,15 @@
 use crate::saber_params::*;
 use crate::U16;
-use std::convert::TryFrom;
-use std::error::Error;
-use std::num::Wrapping;
+use core::num::Wrapping;
 
-/// Serialize coefficients of polynomial `data` into bytestream `bytes`.
-/// Used in Saber's encryption step.
-pub(crate) fn polt2bs(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: [U16; SABER_N]) {
+/// Serialize coefficients of polynomial `data` into bytestream `bytes`
+/// of length `P::SCALEBYTES_KEM`. Used in Saber's encryption step.
+pub(crate) fn polt2bs<P: SaberParams>(bytes: &mut [u8], data: [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
+    debug_assert_eq!(bytes.len(), P::SCALEBYTES_KEM);
 
-    if cfg!(SABER_L_IS_2) {
-        for j in 0..(SABER_N / 8) as usize {
+    if P::ET == 3 {
+        for j in 0..SABER_N / 8 {
             offset_byte = 3 * j;
             offsetdata = 8 * j;
             bytes[offset_byte] = ((data[offsetdata] & U16!(0x7))
@@ -27,16 +26,16 @@ pub(crate) fn polt2bs(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: [U16; SABER_
                 | ((data[offsetdata + 7] & U16!(0x7)) << 5))
                 .0 as u8;
         }
-    } else if cfg!(SABER_L_IS_3) {
-        for j in 0..(SABER_N / 2) as usize {
+    } else if P::ET == 4 {
+        for j in 0..SABER_N / 2 {
             offset_byte = j;
             offsetdata = 2 * j;
             bytes[offset_byte] = ((data[offsetdata] & U16!(0x0f))
                 | ((data[offsetdata + 1] & U16!(0x0f)) << 4))
                 .0 as u8;
         }
-    } else if cfg!(SABER_L_IS_4) {
-        for j in 0..(SABER_N / 4) as usize {
+    } else if P::ET == 6 {
+        for j in 0..SABER_N / 4 {
             offset_byte = 3 * j;
             offsetdata = 4 * j;
             bytes[offset_byte] = ((data[offsetdata] & U16!(0x3f))
@@ -52,13 +51,14 @@ pub(crate) fn polt2bs(bytes: &mut [u8; SABER_SCALEBYTES_KEM], data: [U16; SABER_
     }
 }
 
-/// Deserialize bytestream `bytes` into polynomial coefficients `data`.
-/// Used during Saber's decryption step.
-pub(crate) fn bs2polt(bytes: [u8; SABER_SCALEBYTES_KEM], data: &mut [U16; SABER_N]) {
+/// Deserialize bytestream `bytes` of length `P::SCALEBYTES_KEM` into
+/// polynomial coefficients `data`. Used during Saber's decryption step.
+pub(crate) fn bs2polt<P: SaberParams>(bytes: &[u8], data: &mut [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
+    debug_assert_eq!(bytes.len(), P::SCALEBYTES_KEM);
 
-    if cfg!(SABER_L_IS_2) {
-        for j in 0..(SABER_N / 8) as usize {
+    if P::ET == 3 {
+        for j in 0..SABER_N / 8 {
             offset_byte = 3 * j;
             offsetdata = 8 * j;
             data[offsetdata] = U16!((bytes[offset_byte]) as u16 & 0x07);
@@ -76,15 +76,15 @@ pub(crate) fn bs2polt(bytes: [u8; SABER_SCALEBYTES_KEM], data: &mut [U16; SABER_
             data[offsetdata + 6] = U16!((bytes[offset_byte + 2] as u16 >> 2) & 0x07);
             data[offsetdata + 7] = U16!((bytes[offset_byte + 2] as u16 >> 5) & 0x07);
         }
-    } else if cfg!(SABER_L_IS_3) {
-        for j in 0..(SABER_N / 2) as usize {
+    } else if P::ET == 4 {
+        for j in 0..SABER_N / 2 {
             offset_byte = j;
             offsetdata = 2 * j;
             data[offsetdata] = U16!((bytes[offset_byte] & 0x0f) as u16);
             data[offsetdata + 1] = U16!(((bytes[offset_byte] >> 4) & 0x0f) as u16);
         }
-    } else if cfg!(SABER_L_IS_4) {
-        for j in 0..(SABER_N / 4) as usize {
+    } else if P::ET == 6 {
+        for j in 0..SABER_N / 4 {
             offset_byte = 3 * j;
             offsetdata = 4 * j;
             data[offsetdata] = U16!((bytes[offset_byte] & 0x3f) as u16);
@@ -102,10 +102,10 @@ pub(crate) fn bs2polt(bytes: [u8; SABER_SCALEBYTES_KEM], data: &mut [U16; SABER_
 
 /// Serialize coefficients of polynomial `data` into bytestream `bytes`.
 /// Used during Saber's key generation step.
-fn polq2bs(bytes: &mut [u8; SABER_POLYBYTES], data: [U16; SABER_N]) {
+pub(crate) fn polq2bs(bytes: &mut [u8; SABER_POLYBYTES], data: [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
 
-    for j in 0..(SABER_N / 8) as usize {
+    for j in 0..SABER_N / 8 {
         offset_byte = 13 * j;
         offsetdata = 8 * j;
         bytes[offset_byte] = (data[offsetdata] & U16!(0xff)).0 as u8;
@@ -140,10 +140,10 @@ fn polq2bs(bytes: &mut [u8; SABER_POLYBYTES], data: [U16; SABER_N]) {
 
 /// Deserialize bytestream `bytes` into polynomial coefficients `data`.
 /// Used during Saber's key generation step.
-fn bs2polq(bytes: [u8; SABER_POLYBYTES], data: &mut [U16; SABER_N]) {
+pub(crate) fn bs2polq(bytes: [u8; SABER_POLYBYTES], data: &mut [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
 
-    for j in 0..(SABER_N / 8) as usize {
+    for j in 0..SABER_N / 8 {
         offset_byte = 13 * j;
         offsetdata = 8 * j;
         data[offsetdata] = U16!(
@@ -186,10 +186,10 @@ fn bs2polq(bytes: [u8; SABER_POLYBYTES], data: &mut [U16; SABER_N]) {
 
 /// Serialize coefficients of polynomial `data` into bytestream `bytes`.
 /// Used during Saber's key generation and encryption step.
-fn polp2bs(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: [U16; SABER_N]) {
+pub(crate) fn polp2bs(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
 
-    for j in 0..(SABER_N / 4) as usize {
+    for j in 0..SABER_N / 4 {
         offset_byte = 5 * j;
         offsetdata = 4 * j;
         bytes[offset_byte] = (data[offsetdata] & U16!(0xff)).0 as u8;
@@ -208,10 +208,10 @@ fn polp2bs(bytes: &mut [u8; SABER_POLYCOMPRESSEDBYTES], data: [U16; SABER_N]) {
 
 /// Deserialize bytestream `bytes` into polynomial coefficients `data`.
 /// Used during Saber's decryption step.
-fn bs2polp(bytes: [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [U16; SABER_N]) {
+pub(crate) fn bs2polp(bytes: [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [U16; SABER_N]) {
     let (mut offset_byte, mut offsetdata): (usize, usize);
 
-    for j in 0..(SABER_N / 4) as usize {
+    for j in 0..SABER_N / 4 {
         offset_byte = 5 * j;
         offsetdata = 4 * j;
         data[offsetdata] = U16!(
@@ -232,54 +232,41 @@ fn bs2polp(bytes: [u8; SABER_POLYCOMPRESSEDBYTES], data: &mut [U16; SABER_N]) {
     }
 }
 
-/// Applies `polq2bs` to a vector (i.e. module).
+/// Applies `polq2bs` to a vector (i.e. module) of rank `P::L`.
 /// Takes a vector of polynomials `data` and returns the serialized `bytes`.
-pub(crate) fn polvecq2bs(
-    bytes: &mut [u8; SABER_POLYVECBYTES],
-    data: [[U16; SABER_N]; SABER_L],
-) -> Result<(), Box<dyn Error>> {
-    for j in 0..SABER_L {
-        let tmp = <&mut [u8; SABER_POLYBYTES]>::try_from(
-            &mut bytes[(j * SABER_POLYBYTES)..((j + 1) * SABER_POLYBYTES)],
-        )?;
-
-        polq2bs(tmp, data[j]);
-    }
-    Ok(())
+pub(crate) fn polvecq2bs<P: SaberParams>(bytes: &mut [u8], data: &[[U16; SABER_N]]) {
+    for j in 0..P::L {
+        let mut tmp = [0u8; SABER_POLYBYTES];
+        polq2bs(&mut tmp, data[j]);
+        bytes[(j * SABER_POLYBYTES)..((j + 1) * SABER_POLYBYTES)].copy_from_slice(&tmp);
+    }
 }
 
-/// Applies `bs2polq` to a vector (i.e. module).
+/// Applies `bs2polq` to a vector (i.e. module) of rank `P::L`.
 /// Takes serialized `bytes` and deserializes them to a vector of polynomials `data`.
-pub(crate) fn bs2polvecq(bytes: [u8; SABER_POLYVECBYTES], data: &mut [[U16; SABER_N]; SABER_L]) {
-    for j in 0..SABER_L {
+pub(crate) fn bs2polvecq<P: SaberParams>(bytes: &[u8], data: &mut [[U16; SABER_N]]) {
+    for j in 0..P::L {
         let mut tmp: [u8; SABER_POLYBYTES] = [0; SABER_POLYBYTES];
         tmp.copy_from_slice(&bytes[(j * SABER_POLYBYTES)..((j + 1) * SABER_POLYBYTES)]);
         bs2polq(tmp, &mut data[j]);
     }
 }
 
-/// Applies `polp2bs` to a vector (i.e. module).
+/// Applies `polp2bs` to a vector (i.e. module) of rank `P::L`.
 /// Takes a vector of polynomials `data` and returns the serialized `bytes`.
-pub(crate) fn polvecp2bs(
-    bytes: &mut [u8; SABER_POLYVECCOMPRESSEDBYTES],
-    data: [[U16; SABER_N]; SABER_L],
-) -> Result<(), Box<dyn Error>> {
-    for j in 0..SABER_L {
-        let tmp = <&mut [u8; SABER_POLYCOMPRESSEDBYTES]>::try_from(
-            &mut bytes[(j * SABER_POLYCOMPRESSEDBYTES)..((j + 1) * SABER_POLYCOMPRESSEDBYTES)],
-        )?;
-        polp2bs(tmp, data[j]);
-    }
-    Ok(())
+pub(crate) fn polvecp2bs<P: SaberParams>(bytes: &mut [u8], data: &[[U16; SABER_N]]) {
+    for j in 0..P::L {
+        let mut tmp = [0u8; SABER_POLYCOMPRESSEDBYTES];
+        polp2bs(&mut tmp, data[j]);
+        bytes[(j * SABER_POLYCOMPRESSEDBYTES)..((j + 1) * SABER_POLYCOMPRESSEDBYTES)]
+            .copy_from_slice(&tmp);
+    }
 }
 
-/// Applies `bs2polp` to a vector (i.e. module).
+/// Applies `bs2polp` to a vector (i.e. module) of rank `P::L`.
 /// Takes serialized `bytes` and deserializes them to a vector of polynomials `data`.
-pub(crate) fn bs2polvecp(
-    bytes: [u8; SABER_POLYVECCOMPRESSEDBYTES],
-    data: &mut [[U16; SABER_N]; SABER_L],
-) {
-    for j in 0..SABER_L {
+pub(crate) fn bs2polvecp<P: SaberParams>(bytes: &[u8], data: &mut [[U16; SABER_N]]) {
+    for j in 0..P::L {
         let mut tmp: [u8; SABER_POLYCOMPRESSEDBYTES] = [0; SABER_POLYCOMPRESSEDBYTES];
         tmp.copy_from_slice(
             &bytes[(j * SABER_POLYCOMPRESSEDBYTES)..((j + 1) * SABER_POLYCOMPRESSEDBYTES)],
@@ -290,7 +277,7 @@ pub(crate) fn bs2polvecp(
 
 /// Serializes the message-to-encrypt `data` to a bytestream `bytes`.
 /// Used in Saber's decryption step.
-pub(crate) fn polmsg2bs(bytes: &mut [u8; SABER_KEYBYTES], data: [U16; SABER_N]) {
+pub(crate) fn polmsg2bs(bytes: &mut [u8; SABER_KEYBYTES], data: &[U16; SABER_N]) {
     for b in bytes.iter_mut() {
         *b = 0;
     }
@@ -304,7 +291,7 @@ pub(crate) fn polmsg2bs(bytes: &mut [u8; SABER_KEYBYTES], data: [U16; SABER_N])
 
 /// Deserializes bytestream `bytes` to the message-to-encrypt `data`.
 /// Used in Saber's encryption step.
-pub(crate) fn bs2polmsg(bytes: [u8; SABER_KEYBYTES], data: &mut [U16; SABER_N]) {
+pub(crate) fn bs2polmsg(bytes: &[u8; SABER_KEYBYTES], data: &mut [U16; SABER_N]) {
     for j in 0..SABER_KEYBYTES {
         for i in 0..8 {
             data[j * 8 + i] = U16!(((bytes[j] >> i) & 0x01) as u16);
@@ -313,504 +300,847 @@ pub(crate) fn bs2polmsg(bytes: [u8; SABER_KEYBYTES], data: &mut [U16; SABER_N])
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::link_c_reference::{
-        BS2POLVECp, BS2POLVECq, BS2POLmsg, BS2POLp, BS2POLq, POLVECp2BS, POLVECq2BS, POLmsg2BS,
-        POLp2BS, POLq2BS, BS2POLT, POLT2BS,
-    };
+mod oracle_tests {
+    use crate::oracle::{self, random_poly};
     use crate::pack_unpack::*;
     use rand::Rng;
 
-    #[test]
-    fn test_polt2bs() {
-        const SIZE_BYTES: usize = SABER_SCALEBYTES_KEM;
-        const SIZE_DATA: usize = SABER_N;
-
+    fn check_polt<P: SaberParams>() {
         let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
-
-        let mut data = [U16!(0); SIZE_DATA];
-
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
+        for _ in 0..16 {
+            let data = random_poly(&mut rng, 16);
+            let mut bytes = vec![0u8; P::SCALEBYTES_KEM];
+            polt2bs::<P>(&mut bytes, data);
+            assert_eq!(bytes, oracle::pack(P::ET, &data));
+
+            rng.fill(&mut bytes[..]);
+            let mut data = [U16!(0); SABER_N];
+            bs2polt::<P>(&bytes, &mut data);
+            assert_eq!(data[..], oracle::unpack(P::ET, &bytes)[..]);
         }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
-
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-
-        unsafe {
-            POLT2BS(&mut copy_bytes, &mut copy_data);
-        };
+    }
 
-        polt2bs(&mut bytes, data);
+    #[test]
+    fn test_polt_lightsaber() {
+        check_polt::<LightSaber>();
+    }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check, &data[..]);
-        assert_eq!(copy_data, check);
+    #[test]
+    fn test_polt_saber() {
+        check_polt::<Saber>();
     }
 
     #[test]
-    fn test_bs2polt() {
-        const SIZE_BYTES: usize = SABER_SCALEBYTES_KEM;
-        const SIZE_DATA: usize = SABER_N;
+    fn test_polt_firesaber() {
+        check_polt::<FireSaber>();
+    }
 
+    #[test]
+    fn test_polq() {
         let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
-
-        let mut data = [U16!(0); SIZE_DATA];
-
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
+        for _ in 0..16 {
+            let data = random_poly(&mut rng, 16);
+            let mut bytes = [0u8; SABER_POLYBYTES];
+            polq2bs(&mut bytes, data);
+            assert_eq!(bytes[..], oracle::pack(SABER_EQ, &data)[..]);
+
+            rng.fill(&mut bytes[..]);
+            let mut data = [U16!(0); SABER_N];
+            bs2polq(bytes, &mut data);
+            assert_eq!(data[..], oracle::unpack(SABER_EQ, &bytes)[..]);
         }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+    }
+
+    #[test]
+    fn test_polp() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..16 {
+            let data = random_poly(&mut rng, 16);
+            let mut bytes = [0u8; SABER_POLYCOMPRESSEDBYTES];
+            polp2bs(&mut bytes, data);
+            assert_eq!(bytes[..], oracle::pack(SABER_EP, &data)[..]);
+
+            rng.fill(&mut bytes[..]);
+            let mut data = [U16!(0); SABER_N];
+            bs2polp(bytes, &mut data);
+            assert_eq!(data[..], oracle::unpack(SABER_EP, &bytes)[..]);
         }
+    }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            BS2POLT(&mut copy_bytes, &mut copy_data);
-        };
+    fn check_polvec<P: SaberParams>() {
+        let mut rng = rand::thread_rng();
+        let mut data = [[U16!(0); SABER_N]; SABER_L_MAX];
+        for poly in data.iter_mut().take(P::L) {
+            *poly = random_poly(&mut rng, 16);
+        }
+        let coefficients = data[..P::L].concat();
+
+        let mut bytes = vec![0u8; P::POLYVECBYTES];
+        polvecq2bs::<P>(&mut bytes, &data);
+        assert_eq!(bytes, oracle::pack(SABER_EQ, &coefficients));
+        let mut unpacked = [[U16!(0); SABER_N]; SABER_L_MAX];
+        bs2polvecq::<P>(&bytes, &mut unpacked);
+        assert_eq!(unpacked[..P::L].concat(), oracle::unpack(SABER_EQ, &bytes));
+
+        let mut bytes = vec![0u8; P::POLYVECCOMPRESSEDBYTES];
+        polvecp2bs::<P>(&mut bytes, &data);
+        assert_eq!(bytes, oracle::pack(SABER_EP, &coefficients));
+        let mut unpacked = [[U16!(0); SABER_N]; SABER_L_MAX];
+        bs2polvecp::<P>(&bytes, &mut unpacked);
+        assert_eq!(unpacked[..P::L].concat(), oracle::unpack(SABER_EP, &bytes));
+    }
 
-        bs2polt(bytes, &mut data);
+    #[test]
+    fn test_polvec_lightsaber() {
+        check_polvec::<LightSaber>();
+    }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
+    #[test]
+    fn test_polvec_saber() {
+        check_polvec::<Saber>();
     }
 
     #[test]
-    fn test_polq2bs() {
-        const SIZE_BYTES: usize = SABER_POLYBYTES;
-        const SIZE_DATA: usize = SABER_N;
+    fn test_polvec_firesaber() {
+        check_polvec::<FireSaber>();
+    }
 
+    #[test]
+    fn test_polmsg() {
         let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
-
-        let mut data = [U16!(0); SIZE_DATA];
+        let data = random_poly(&mut rng, 16);
+        let mut bytes = [0u8; SABER_KEYBYTES];
+        polmsg2bs(&mut bytes, &data);
+        assert_eq!(bytes[..], oracle::pack(1, &data)[..]);
+
+        rng.fill(&mut bytes[..]);
+        let mut data = [U16!(0); SABER_N];
+        bs2polmsg(&bytes, &mut data);
+        assert_eq!(data[..], oracle::unpack(1, &bytes)[..]);
+    }
+}
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
+#[cfg(test)]
+mod proptests {
+    use crate::pack_unpack::*;
+    use proptest::prelude::*;
+
+    /// Polynomial with arbitrary 16-bit coefficients
+    fn poly() -> impl Strategy<Value = [U16; SABER_N]> {
+        prop::collection::vec(any::<u16>(), SABER_N).prop_map(|v| {
+            let mut a = [U16!(0); SABER_N];
+            for (x, y) in a.iter_mut().zip(v) {
+                *x = U16!(y);
+            }
+            a
+        })
+    }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        //copy_data.copy_from_slice(&data[..]);
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            POLq2BS(&mut copy_bytes, &mut copy_data);
-        };
+    /// `data` with all coefficients reduced modulo `2^bits`
+    fn reduce(data: [U16; SABER_N], bits: usize) -> [U16; SABER_N] {
+        data.map(|x| x & U16!((1 << bits) - 1))
+    }
 
-        polq2bs(&mut bytes, data);
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
+    fn polt_roundtrip<P: SaberParams>(data: [U16; SABER_N]) -> Result<(), TestCaseError> {
+        let mut bytes = [0u8; SABER_N];
+        let bytes = &mut bytes[..P::SCALEBYTES_KEM];
+        polt2bs::<P>(bytes, data);
+        let mut unpacked = [U16!(0); SABER_N];
+        bs2polt::<P>(bytes, &mut unpacked);
+        prop_assert_eq!(unpacked, reduce(data, P::ET));
+        Ok(())
     }
 
-    #[test]
-    fn test_bs2polq() {
-        const SIZE_BYTES: usize = SABER_POLYBYTES;
-        const SIZE_DATA: usize = SABER_N;
+    proptest! {
+        #[test]
+        fn test_polq_roundtrip(data in poly()) {
+            let mut bytes = [0u8; SABER_POLYBYTES];
+            polq2bs(&mut bytes, data);
+            let mut unpacked = [U16!(0); SABER_N];
+            bs2polq(bytes, &mut unpacked);
+            prop_assert_eq!(unpacked, reduce(data, SABER_EQ));
+        }
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+        #[test]
+        fn test_polp_roundtrip(data in poly()) {
+            let mut bytes = [0u8; SABER_POLYCOMPRESSEDBYTES];
+            polp2bs(&mut bytes, data);
+            let mut unpacked = [U16!(0); SABER_N];
+            bs2polp(bytes, &mut unpacked);
+            prop_assert_eq!(unpacked, reduce(data, SABER_EP));
+        }
 
-        let mut data = [U16!(0); SIZE_DATA];
+        #[test]
+        fn test_polt_roundtrip(data in poly()) {
+            polt_roundtrip::<LightSaber>(data)?;
+            polt_roundtrip::<Saber>(data)?;
+            polt_roundtrip::<FireSaber>(data)?;
+        }
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
+        #[test]
+        fn test_polmsg_roundtrip(data in poly()) {
+            let mut bytes = [0u8; SABER_KEYBYTES];
+            polmsg2bs(&mut bytes, &data);
+            let mut unpacked = [U16!(0); SABER_N];
+            bs2polmsg(&bytes, &mut unpacked);
+            prop_assert_eq!(unpacked, reduce(data, 1));
         }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+
+        #[test]
+        fn test_bs2polq_roundtrip(bytes in prop::collection::vec(any::<u8>(), SABER_POLYBYTES)) {
+            // every bytestring encodes a polynomial
+            let bytes: [u8; SABER_POLYBYTES] = bytes.try_into().unwrap();
+            let mut data = [U16!(0); SABER_N];
+            bs2polq(bytes, &mut data);
+            let mut packed = [0u8; SABER_POLYBYTES];
+            polq2bs(&mut packed, data);
+            prop_assert_eq!(packed, bytes);
         }
+    }
+}
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            BS2POLq(&mut copy_bytes, &mut copy_data);
+#[cfg(all(test, feature = "cref"))]
+mod tests {
+    crate::link_c_reference::for_each_variant! {
+        use cref::{
+            BS2POLVECp, BS2POLVECq, BS2POLmsg, BS2POLp, BS2POLq, POLVECp2BS, POLVECq2BS, POLmsg2BS,
+            POLp2BS, POLq2BS, BS2POLT, POLT2BS,
         };
+        use cref::{
+            CRefParams, SABER_L, SABER_POLYVECBYTES, SABER_POLYVECCOMPRESSEDBYTES, SABER_SCALEBYTES_KEM,
+        };
+        use crate::pack_unpack::*;
+        use rand::Rng;
 
-        bs2polq(bytes, &mut data);
+        #[test]
+        fn test_polt2bs() {
+            const SIZE_BYTES: usize = SABER_SCALEBYTES_KEM;
+            const SIZE_DATA: usize = SABER_N;
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
-    }
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-    #[test]
-    fn test_polp2bs() {
-        const SIZE_BYTES: usize = SABER_POLYCOMPRESSEDBYTES;
-        const SIZE_DATA: usize = SABER_N;
+            let mut data = [U16!(0); SIZE_DATA];
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        let mut data = [U16!(0); SIZE_DATA];
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
+            unsafe {
+                POLT2BS(&mut copy_bytes, &mut copy_data);
+            };
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            POLp2BS(&mut copy_bytes, &mut copy_data);
-        };
+            polt2bs::<CRefParams>(&mut bytes, data);
 
-        polp2bs(&mut bytes, data);
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check, &data[..]);
+            assert_eq!(copy_data, check);
+        }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
-    }
+        #[test]
+        fn test_bs2polt() {
+            const SIZE_BYTES: usize = SABER_SCALEBYTES_KEM;
+            const SIZE_DATA: usize = SABER_N;
 
-    #[test]
-    fn test_bs2polp() {
-        const SIZE_BYTES: usize = SABER_POLYCOMPRESSEDBYTES;
-        const SIZE_DATA: usize = SABER_N;
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            let mut data = [U16!(0); SIZE_DATA];
 
-        let mut data = [U16!(0); SIZE_DATA];
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                BS2POLT(&mut copy_bytes, &mut copy_data);
+            };
+
+            bs2polt::<CRefParams>(&bytes, &mut data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            BS2POLp(&mut copy_bytes, &mut copy_data);
-        };
+        #[test]
+        fn test_polq2bs() {
+            const SIZE_BYTES: usize = SABER_POLYBYTES;
+            const SIZE_DATA: usize = SABER_N;
 
-        bs2polp(bytes, &mut data);
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
-    }
+            let mut data = [U16!(0); SIZE_DATA];
 
-    #[test]
-    fn test_polvecq2bs() {
-        const SIZE_BYTES: usize = SABER_POLYVECBYTES;
-        const SIZE_DATA: usize = SABER_N;
-        const SIZE_DATA2: usize = SABER_L;
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            //copy_data.copy_from_slice(&data[..]);
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                POLq2BS(&mut copy_bytes, &mut copy_data);
+            };
+
+            polq2bs(&mut bytes, data);
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
+        }
 
-        let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+        #[test]
+        fn test_bs2polq() {
+            const SIZE_BYTES: usize = SABER_POLYBYTES;
+            const SIZE_DATA: usize = SABER_N;
 
-        let mut ind = 0;
-        while ind < data.len() {
-            let mut ind2 = 0;
-            while ind2 < data[ind].len() {
-                data[ind][ind2] = rng.gen();
-                ind2 += 1;
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [U16!(0); SIZE_DATA];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
             }
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                BS2POLq(&mut copy_bytes, &mut copy_data);
+            };
+
+            bs2polq(bytes, &mut data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
-        unsafe {
-            POLVECq2BS(&mut copy_bytes, &mut copy_data);
-        };
 
-        polvecq2bs(&mut bytes, data).expect("polvecq2bs failed!");
+        #[test]
+        fn test_polp2bs() {
+            const SIZE_BYTES: usize = SABER_POLYCOMPRESSEDBYTES;
+            const SIZE_DATA: usize = SABER_N;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [U16!(0); SIZE_DATA];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut check[i][..], &data[i][..]);
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                POLp2BS(&mut copy_bytes, &mut copy_data);
+            };
+
+            polp2bs(&mut bytes, data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
-        assert_eq!(copy_data, check);
-    }
 
-    #[test]
-    fn test_bs2polvecq() {
-        const SIZE_BYTES: usize = SABER_POLYVECBYTES;
-        const SIZE_DATA: usize = SABER_N;
-        const SIZE_DATA2: usize = SABER_L;
+        #[test]
+        fn test_bs2polp() {
+            const SIZE_BYTES: usize = SABER_POLYCOMPRESSEDBYTES;
+            const SIZE_DATA: usize = SABER_N;
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-        let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+            let mut data = [U16!(0); SIZE_DATA];
 
-        let mut ind = 0;
-        while ind < data.len() {
-            let mut ind2 = 0;
-            while ind2 < data[ind].len() {
-                data[ind][ind2] = rng.gen();
-                ind2 += 1;
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
             }
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                BS2POLp(&mut copy_bytes, &mut copy_data);
+            };
+
+            bs2polp(bytes, &mut data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
-        unsafe {
-            BS2POLVECq(&mut copy_bytes, &mut copy_data);
-        };
 
-        bs2polvecq(bytes, &mut data);
+        #[test]
+        fn test_polvecq2bs() {
+            const SIZE_BYTES: usize = SABER_POLYVECBYTES;
+            const SIZE_DATA: usize = SABER_N;
+            const SIZE_DATA2: usize = SABER_L;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                let mut ind2 = 0;
+                while ind2 < data[ind].len() {
+                    data[ind][ind2] = rng.gen();
+                    ind2 += 1;
+                }
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
+
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            }
+            unsafe {
+                POLVECq2BS(&mut copy_bytes, &mut copy_data);
+            };
+
+            polvecq2bs::<CRefParams>(&mut bytes, &data);
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut check[i][..], &data[i][..]);
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut check[i][..], &data[i][..]);
+            }
+            assert_eq!(copy_data, check);
         }
-        assert_eq!(copy_data, check);
-    }
 
-    #[test]
-    fn test_polvecp2bs() {
-        const SIZE_BYTES: usize = SABER_POLYVECCOMPRESSEDBYTES;
-        const SIZE_DATA: usize = SABER_N;
-        const SIZE_DATA2: usize = SABER_L;
+        #[test]
+        fn test_bs2polvecq() {
+            const SIZE_BYTES: usize = SABER_POLYVECBYTES;
+            const SIZE_DATA: usize = SABER_N;
+            const SIZE_DATA2: usize = SABER_L;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                let mut ind2 = 0;
+                while ind2 < data[ind].len() {
+                    data[ind][ind2] = rng.gen();
+                    ind2 += 1;
+                }
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            }
+            unsafe {
+                BS2POLVECq(&mut copy_bytes, &mut copy_data);
+            };
 
-        let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+            bs2polvecq::<CRefParams>(&bytes, &mut data);
 
-        let mut ind = 0;
-        while ind < data.len() {
-            let mut ind2 = 0;
-            while ind2 < data[ind].len() {
-                data[ind][ind2] = rng.gen();
-                ind2 += 1;
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut check[i][..], &data[i][..]);
             }
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+            assert_eq!(copy_data, check);
         }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
-        }
-        unsafe {
-            POLVECp2BS(&mut copy_bytes, &mut copy_data);
-        };
+        #[test]
+        fn test_polvecp2bs() {
+            const SIZE_BYTES: usize = SABER_POLYVECCOMPRESSEDBYTES;
+            const SIZE_DATA: usize = SABER_N;
+            const SIZE_DATA2: usize = SABER_L;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                let mut ind2 = 0;
+                while ind2 < data[ind].len() {
+                    data[ind][ind2] = rng.gen();
+                    ind2 += 1;
+                }
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
+
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            }
+            unsafe {
+                POLVECp2BS(&mut copy_bytes, &mut copy_data);
+            };
 
-        polvecp2bs(&mut bytes, data).expect("polvecp2bs failed!");
+            polvecp2bs::<CRefParams>(&mut bytes, &data);
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut check[i][..], &data[i][..]);
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut check[i][..], &data[i][..]);
+            }
+            assert_eq!(copy_data, check);
         }
-        assert_eq!(copy_data, check);
-    }
 
-    #[test]
-    fn test_bs2polvecp() {
-        const SIZE_BYTES: usize = SABER_POLYVECCOMPRESSEDBYTES;
-        const SIZE_DATA: usize = SABER_N;
-        const SIZE_DATA2: usize = SABER_L;
+        #[test]
+        fn test_bs2polvecp() {
+            const SIZE_BYTES: usize = SABER_POLYVECCOMPRESSEDBYTES;
+            const SIZE_DATA: usize = SABER_N;
+            const SIZE_DATA2: usize = SABER_L;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
+
+            let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+
+            let mut ind = 0;
+            while ind < data.len() {
+                let mut ind2 = 0;
+                while ind2 < data[ind].len() {
+                    data[ind][ind2] = rng.gen();
+                    ind2 += 1;
+                }
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
+            }
+            unsafe {
+                BS2POLVECp(&mut copy_bytes, &mut copy_data);
+            };
 
-        let mut data = [[U16!(0); SIZE_DATA]; SIZE_DATA2];
+            bs2polvecp::<CRefParams>(&bytes, &mut data);
 
-        let mut ind = 0;
-        while ind < data.len() {
-            let mut ind2 = 0;
-            while ind2 < data[ind].len() {
-                data[ind][ind2] = rng.gen();
-                ind2 += 1;
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
+            for i in 0..SIZE_DATA2 {
+                wrappedu162u16(&mut check[i][..], &data[i][..]);
             }
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+            assert_eq!(copy_data, check);
         }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [[u16; SIZE_DATA]; SIZE_DATA2] = [[0; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut copy_data[i][..], &data[i][..]);
-        }
-        unsafe {
-            BS2POLVECp(&mut copy_bytes, &mut copy_data);
-        };
+        #[test]
+        fn test_bs2polmsg() {
+            const SIZE_BYTES: usize = SABER_KEYBYTES;
+            const SIZE_DATA: usize = SABER_N;
+
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-        bs2polvecp(bytes, &mut data);
+            let mut data = [U16!(0); SIZE_DATA];
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [[0u16; SIZE_DATA]; SIZE_DATA2];
-        for i in 0..SIZE_DATA2 {
-            wrappedu162u16(&mut check[i][..], &data[i][..]);
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
+
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                BS2POLmsg(&mut copy_bytes, &mut copy_data);
+            };
+
+            bs2polmsg(&bytes, &mut data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
-        assert_eq!(copy_data, check);
-    }
 
-    #[test]
-    fn test_bs2polmsg() {
-        const SIZE_BYTES: usize = SABER_KEYBYTES;
-        const SIZE_DATA: usize = SABER_N;
+        #[test]
+        fn test_polmsg2bs() {
+            const SIZE_BYTES: usize = SABER_KEYBYTES;
+            const SIZE_DATA: usize = SABER_N;
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+            let mut rng = rand::thread_rng();
+            let mut bytes = [0; SIZE_BYTES];
 
-        let mut data = [U16!(0); SIZE_DATA];
+            let mut data = [U16!(0); SIZE_DATA];
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
+            let mut ind = 0;
+            while ind < data.len() {
+                data[ind] = rng.gen();
+                ind += 1;
+            }
+            ind = 0;
+            while ind < bytes.len() {
+                bytes[ind] = rng.gen();
+                ind += 1;
+            }
+
+            //to check if equal
+            let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
+            copy_bytes.copy_from_slice(&bytes[..]);
+            let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
+            wrappedu162u16(&mut copy_data[..], &data[..]);
+            unsafe {
+                POLmsg2BS(&mut copy_bytes, &mut copy_data);
+            };
+
+            polmsg2bs(&mut bytes, &data);
+
+            assert_eq!(copy_bytes, bytes);
+            let mut check = [0u16; SIZE_DATA];
+            wrappedu162u16(&mut check[..], &data[..]);
+            assert_eq!(copy_data, check);
         }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
+    }
+}
+
+/// Kani proof harnesses, run with `cargo kani`. For all inputs, canonical coefficients
+/// survive a roundtrip through the bytestream, and so does every bytestream.
+/// Kani also checks that no shift, index or arithmetic operation panics.
+#[cfg(kani)]
+mod verification {
+    use crate::pack_unpack::*;
+
+    /// Polynomial with arbitrary coefficients of at most `bits` bits
+    fn any_poly(bits: usize) -> [U16; SABER_N] {
+        let data: [u16; SABER_N] = kani::any();
+        for x in data.iter() {
+            kani::assume(*x >> bits == 0);
         }
+        data.map(Wrapping)
+    }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            BS2POLmsg(&mut copy_bytes, &mut copy_data);
-        };
+    /// Polynomial with arbitrary 16-bit coefficients
+    fn any_wide_poly() -> [U16; SABER_N] {
+        kani::any::<[u16; SABER_N]>().map(Wrapping)
+    }
 
-        bs2polmsg(bytes, &mut data);
+    macro_rules! polt_harnesses {
+        ($roundtrip:ident, $bytes_roundtrip:ident, $params:ty) => {
+            #[kani::proof]
+            #[kani::unwind(257)]
+            fn $roundtrip() {
+                let data = any_poly(<$params>::ET);
+                let mut bytes = [0u8; <$params>::SCALEBYTES_KEM];
+                polt2bs::<$params>(&mut bytes, data);
+                let mut unpacked = [U16!(0); SABER_N];
+                bs2polt::<$params>(&bytes, &mut unpacked);
+                assert!(unpacked == data);
+            }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
+            #[kani::proof]
+            #[kani::unwind(257)]
+            fn $bytes_roundtrip() {
+                let bytes: [u8; <$params>::SCALEBYTES_KEM] = kani::any();
+                let mut data = [U16!(0); SABER_N];
+                bs2polt::<$params>(&bytes, &mut data);
+                let mut packed = [0u8; <$params>::SCALEBYTES_KEM];
+                polt2bs::<$params>(&mut packed, data);
+                assert!(packed == bytes);
+            }
+        };
     }
 
-    #[test]
-    fn test_polmsg2bs() {
-        const SIZE_BYTES: usize = SABER_KEYBYTES;
-        const SIZE_DATA: usize = SABER_N;
+    polt_harnesses!(
+        polt_roundtrip_lightsaber,
+        bytes_t_roundtrip_lightsaber,
+        LightSaber
+    );
+    polt_harnesses!(polt_roundtrip_saber, bytes_t_roundtrip_saber, Saber);
+    polt_harnesses!(
+        polt_roundtrip_firesaber,
+        bytes_t_roundtrip_firesaber,
+        FireSaber
+    );
+
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn polq_roundtrip() {
+        let data = any_poly(SABER_EQ);
+        let mut bytes = [0u8; SABER_POLYBYTES];
+        polq2bs(&mut bytes, data);
+        let mut unpacked = [U16!(0); SABER_N];
+        bs2polq(bytes, &mut unpacked);
+        assert!(unpacked == data);
+    }
 
-        let mut rng = rand::thread_rng();
-        let mut bytes = [0; SIZE_BYTES];
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn bytes_q_roundtrip() {
+        let bytes: [u8; SABER_POLYBYTES] = kani::any();
+        let mut data = [U16!(0); SABER_N];
+        bs2polq(bytes, &mut data);
+        let mut packed = [0u8; SABER_POLYBYTES];
+        polq2bs(&mut packed, data);
+        assert!(packed == bytes);
+    }
 
-        let mut data = [U16!(0); SIZE_DATA];
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn polp_roundtrip() {
+        let data = any_poly(SABER_EP);
+        let mut bytes = [0u8; SABER_POLYCOMPRESSEDBYTES];
+        polp2bs(&mut bytes, data);
+        let mut unpacked = [U16!(0); SABER_N];
+        bs2polp(bytes, &mut unpacked);
+        assert!(unpacked == data);
+    }
 
-        let mut ind = 0;
-        while ind < data.len() {
-            data[ind] = rng.gen();
-            ind += 1;
-        }
-        ind = 0;
-        while ind < bytes.len() {
-            bytes[ind] = rng.gen();
-            ind += 1;
-        }
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn bytes_p_roundtrip() {
+        let bytes: [u8; SABER_POLYCOMPRESSEDBYTES] = kani::any();
+        let mut data = [U16!(0); SABER_N];
+        bs2polp(bytes, &mut data);
+        let mut packed = [0u8; SABER_POLYCOMPRESSEDBYTES];
+        polp2bs(&mut packed, data);
+        assert!(packed == bytes);
+    }
 
-        //to check if equal
-        let mut copy_bytes: [u8; SIZE_BYTES] = [0; SIZE_BYTES];
-        copy_bytes.copy_from_slice(&bytes[..]);
-        let mut copy_data: [u16; SIZE_DATA] = [0; SIZE_DATA];
-        wrappedu162u16(&mut copy_data[..], &data[..]);
-        unsafe {
-            POLmsg2BS(&mut copy_bytes, &mut copy_data);
-        };
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn polmsg_roundtrip() {
+        let data = any_poly(1);
+        let mut bytes = [0u8; SABER_KEYBYTES];
+        polmsg2bs(&mut bytes, &data);
+        let mut unpacked = [U16!(0); SABER_N];
+        bs2polmsg(&bytes, &mut unpacked);
+        assert!(unpacked == data);
+    }
 
-        polmsg2bs(&mut bytes, data);
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn bytes_msg_roundtrip() {
+        let bytes: [u8; SABER_KEYBYTES] = kani::any();
+        let mut data = [U16!(0); SABER_N];
+        bs2polmsg(&bytes, &mut data);
+        let mut packed = [0u8; SABER_KEYBYTES];
+        polmsg2bs(&mut packed, &data);
+        assert!(packed == bytes);
+    }
 
-        assert_eq!(copy_bytes, bytes);
-        let mut check = [0u16; SIZE_DATA];
-        wrappedu162u16(&mut check[..], &data[..]);
-        assert_eq!(copy_data, check);
+    /// Serializing drops the higher bits of arbitrary coefficients without panicking.
+    #[kani::proof]
+    #[kani::unwind(257)]
+    fn pack_any_coefficients() {
+        polt2bs::<LightSaber>(&mut [0u8; LightSaber::SCALEBYTES_KEM], any_wide_poly());
+        polt2bs::<Saber>(&mut [0u8; Saber::SCALEBYTES_KEM], any_wide_poly());
+        polt2bs::<FireSaber>(&mut [0u8; FireSaber::SCALEBYTES_KEM], any_wide_poly());
+        polq2bs(&mut [0u8; SABER_POLYBYTES], any_wide_poly());
+        polp2bs(&mut [0u8; SABER_POLYCOMPRESSEDBYTES], any_wide_poly());
+        polmsg2bs(&mut [0u8; SABER_KEYBYTES], &any_wide_poly());
     }
 }
@@ -3,207 +3,290 @@ use crate::fips202::shake_128;
 use crate::pack_unpack::bs2polvecq;
 use crate::poly_mul::poly_mul_acc;
 use crate::saber_params::{
-    SABER_L, SABER_N, SABER_NOISE_SEEDBYTES, SABER_POLYCOINBYTES, SABER_POLYVECBYTES,
+    SaberParams, SABER_L_MAX, SABER_MU_MAX, SABER_N, SABER_NOISE_SEEDBYTES, SABER_POLYBYTES,
     SABER_SEEDBYTES, U16,
 };
-use std::error::Error;
+use crate::wipe::wipe;
 
 /// Let `a` be a vector of vectors of polynomials (a vector of module elements) and thus a matrix.
 /// Let `s` be a vector of polynomials (a module element).
 /// Let `×` denote matrix multiplication and `M^t` denote the transpose of matrix `M`.
 /// If `transpose`, compute `a^t × s`. Else compute `a × s`.
+/// Only the leading `P::L × P::L` entries of `a` are considered.
 /// The product is returned as vector `res`.
-pub(crate) fn matrix_vector_mul(
-    a: [[[U16; SABER_N]; SABER_L]; SABER_L],
-    s: [[U16; SABER_N]; SABER_L],
-    res: &mut [[U16; SABER_N]; SABER_L],
+pub(crate) fn matrix_vector_mul<P: SaberParams>(
+    a: &[[[U16; SABER_N]; SABER_L_MAX]],
+    s: &[[U16; SABER_N]],
+    res: &mut [[U16; SABER_N]],
     transpose: bool,
 ) {
-    for i in 0..SABER_L {
-        for j in 0..SABER_L {
+    for i in 0..P::L {
+        for j in 0..P::L {
             if transpose {
-                poly_mul_acc(a[j][i], s[j], &mut res[i]);
+                poly_mul_acc(&a[j][i], &s[j], &mut res[i]);
             } else {
-                poly_mul_acc(a[i][j], s[j], &mut res[i]);
+                poly_mul_acc(&a[i][j], &s[j], &mut res[i]);
             }
         }
     }
 }
 
-/// Compute the inner product between vectors `b` and `s`.
+/// Compute the inner product between vectors `b` and `s` of rank `P::L`.
 /// The scalar is returned as `res`.
-pub(crate) fn inner_prod(
-    b: [[U16; SABER_N]; SABER_L],
-    s: [[U16; SABER_N]; SABER_L],
+pub(crate) fn inner_prod<P: SaberParams>(
+    b: &[[U16; SABER_N]],
+    s: &[[U16; SABER_N]],
     res: &mut [U16; SABER_N],
 ) {
-    for j in 0..SABER_L {
-        poly_mul_acc(b[j], s[j], res);
+    for j in 0..P::L {
+        poly_mul_acc(&b[j], &s[j], res);
     }
 }
 
 /// Use `seed` to derive matrix `a` from it.
 /// Matrix generation is used as part of the key generation step
 /// and encryption reproduces the results.
-pub(crate) fn gen_matrix(
-    a: &mut [[[U16; SABER_N]; SABER_L]; SABER_L],
+pub(crate) fn gen_matrix<P: SaberParams>(
+    a: &mut [[[U16; SABER_N]; SABER_L_MAX]],
     seed: [u8; SABER_SEEDBYTES],
-) -> Result<(), Box<dyn Error>> {
-    let mut buf = [0u8; SABER_L * SABER_POLYVECBYTES];
-    shake_128(&mut buf, &seed)?;
-    for i in 0..SABER_L {
-        let mut tmp: [u8; SABER_POLYVECBYTES] = [0; SABER_POLYVECBYTES];
-        tmp.copy_from_slice(&buf[(i * SABER_POLYVECBYTES)..((i + 1) * SABER_POLYVECBYTES)]);
-        bs2polvecq(tmp, &mut a[i])
+) {
+    let mut buf = [0u8; SABER_L_MAX * SABER_L_MAX * SABER_POLYBYTES];
+    let buf = &mut buf[..P::L * P::POLYVECBYTES];
+    shake_128(buf, &seed);
+    for (i, row) in buf.chunks_exact(P::POLYVECBYTES).enumerate() {
+        bs2polvecq::<P>(row, &mut a[i])
     }
-    Ok(())
 }
 
 /// Use `seed` to derive secret vector `s` from it.
 /// Matrix generation is used as part of the key generation step
 /// and encryption reproduces the results.
-pub(crate) fn gen_secret(
-    s: &mut [[U16; SABER_N]; SABER_L],
-    seed: [u8; SABER_NOISE_SEEDBYTES],
-) -> Result<(), Box<dyn Error>> {
-    let mut buf = [0u8; SABER_L * SABER_POLYCOINBYTES];
-
-    shake_128(&mut buf, &seed)?;
-    for i in 0..SABER_L {
-        let mut tmp: [u8; SABER_POLYCOINBYTES] = [0u8; SABER_POLYCOINBYTES];
-        tmp.copy_from_slice(&buf[(i * SABER_POLYCOINBYTES)..((i + 1) * SABER_POLYCOINBYTES)]);
-        cbd(&mut s[i], tmp);
+pub(crate) fn gen_secret<P: SaberParams>(
+    s: &mut [[U16; SABER_N]],
+    seed: &[u8; SABER_NOISE_SEEDBYTES],
+) {
+    let mut buf = [0u8; SABER_L_MAX * SABER_MU_MAX * SABER_N / 8];
+    let coins = &mut buf[..P::L * P::POLYCOINBYTES];
+
+    shake_128(coins, seed);
+    for (i, coins) in coins.chunks_exact(P::POLYCOINBYTES).enumerate() {
+        cbd::<P>(&mut s[i], coins);
     }
-    Ok(())
+    wipe!(buf);
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::link_c_reference::{GenMatrix, GenSecret, InnerProd, MatrixVectorMul};
-    use crate::poly::{gen_matrix, gen_secret, inner_prod, matrix_vector_mul};
-    use crate::saber_params::{wrappedu162u16, SABER_L, SABER_N, SABER_SEEDBYTES, U16};
+mod oracle_tests {
+    use crate::fips202::shake_128;
+    use crate::oracle::{self, random_poly};
+    use crate::poly::{gen_secret, inner_prod, matrix_vector_mul};
+    use crate::saber_params::{
+        FireSaber, LightSaber, Saber, SaberParams, SABER_EQ, SABER_L_MAX, SABER_N,
+        SABER_NOISE_SEEDBYTES, U16,
+    };
     use crate::U16;
+    use core::num::Wrapping;
     use rand::Rng;
-    use std::num::Wrapping;
 
-    #[test]
-    fn test_matrix_vector_mul() {
-        let mut a: [[[U16; SABER_N]; SABER_L]; SABER_L] = [[[U16!(0); SABER_N]; SABER_L]; SABER_L];
-        let mut s: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
-        let mut a_c: [[[u16; SABER_N]; SABER_L]; SABER_L] = [[[0u16; SABER_N]; SABER_L]; SABER_L];
-        let mut s_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        let mut res1: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        let mut res2: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
-        let transpose: i16 = 0;
+    fn assert_eq_mod_q(a: &[[U16; SABER_N]], b: &[[U16; SABER_N]]) {
+        for (x, y) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert_eq!(x.0 % (1 << SABER_EQ), y.0 % (1 << SABER_EQ));
+        }
+    }
 
+    fn check_products<P: SaberParams>() {
         let mut rng = rand::thread_rng();
+        let mut a = [[[U16!(0); SABER_N]; SABER_L_MAX]; SABER_L_MAX];
+        let mut s = [[U16!(0); SABER_N]; SABER_L_MAX];
+        for (row, poly) in a.iter_mut().zip(s.iter_mut()).take(P::L) {
+            for entry in row.iter_mut().take(P::L) {
+                *entry = random_poly(&mut rng, SABER_EQ);
+            }
+            *poly = random_poly(&mut rng, SABER_EQ);
+        }
 
-        for i in 0..SABER_L {
-            for j in 0..SABER_L {
-                for x in 0..SABER_N {
-                    let a_x: u16 = rng.gen_range(0..8192);
-                    a[i][j][x] = U16!(a_x);
-                    a_c[i][j][x] = a_x;
+        for transpose in [false, true] {
+            let mut res = [[U16!(0); SABER_N]; SABER_L_MAX];
+            matrix_vector_mul::<P>(&a, &s, &mut res, transpose);
+            let mut expected = [[U16!(0); SABER_N]; SABER_L_MAX];
+            for i in 0..P::L {
+                for j in 0..P::L {
+                    let entry = if transpose { &a[j][i] } else { &a[i][j] };
+                    oracle::poly_mul_acc(entry, &s[j], &mut expected[i]);
                 }
             }
+            assert_eq_mod_q(&res, &expected);
         }
-        for i in 0..SABER_L {
-            for j in 0..SABER_N {
-                let s_x: u16 = rng.gen_range(0..8192);
-                s[i][j] = U16!(s_x);
-                s_c[i][j] = s_x;
-            }
+
+        let mut res = [U16!(0); SABER_N];
+        inner_prod::<P>(&a[0], &s, &mut res);
+        let mut expected = [U16!(0); SABER_N];
+        for j in 0..P::L {
+            oracle::poly_mul_acc(&a[0][j], &s[j], &mut expected);
         }
+        assert_eq_mod_q(&[res], &[expected]);
+    }
 
-        unsafe { MatrixVectorMul(&mut a_c, &mut s_c, &mut res1, transpose) };
-        matrix_vector_mul(a, s, &mut res2, transpose == 1);
+    fn check_gen_secret<P: SaberParams>() {
+        let mut rng = rand::thread_rng();
+        let mut seed = [0u8; SABER_NOISE_SEEDBYTES];
+        rng.fill(&mut seed);
 
-        let mut check: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        for i in 0..SABER_L {
-            wrappedu162u16(&mut check[i][..], &res2[i][..]);
+        let mut s = [[U16!(0); SABER_N]; SABER_L_MAX];
+        gen_secret::<P>(&mut s, &seed);
+        let mut coins = vec![0u8; P::L * P::POLYCOINBYTES];
+        shake_128(&mut coins, &seed);
+        for (i, coins) in coins.chunks_exact(P::POLYCOINBYTES).enumerate() {
+            assert_eq!(s[i], oracle::cbd(P::MU, coins));
         }
-        assert_eq!(res1, check);
-        unsafe { MatrixVectorMul(&mut a_c, &mut s_c, &mut res1, 1) };
-        matrix_vector_mul(a, s, &mut res2, true);
+    }
 
-        let mut check: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        for i in 0..SABER_L {
-            wrappedu162u16(&mut check[i][..], &res2[i][..]);
-        }
-        assert_eq!(res1, check);
+    #[test]
+    fn test_lightsaber() {
+        check_products::<LightSaber>();
+        check_gen_secret::<LightSaber>();
     }
 
     #[test]
-    fn test_inner_prod() {
-        let mut b: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
-        let mut s: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
-        let mut b_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        let mut s_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
-        let mut res1: [u16; SABER_N] = [0u16; SABER_N];
-        let mut res2: [U16; SABER_N] = [U16!(0); SABER_N];
+    fn test_saber() {
+        check_products::<Saber>();
+        check_gen_secret::<Saber>();
+    }
 
-        let mut rng = rand::thread_rng();
+    #[test]
+    fn test_firesaber() {
+        check_products::<FireSaber>();
+        check_gen_secret::<FireSaber>();
+    }
+}
+
+#[cfg(all(test, feature = "cref"))]
+mod tests {
+    crate::link_c_reference::for_each_variant! {
+        use cref::{
+            CRefParams, GenMatrix, GenSecret, InnerProd, MatrixVectorMul, SABER_L,
+        };
+        use crate::poly::{gen_matrix, gen_secret, inner_prod, matrix_vector_mul};
+        use crate::saber_params::{wrappedu162u16, SABER_L_MAX, SABER_N, SABER_SEEDBYTES, U16};
+        use crate::U16;
+        use core::num::Wrapping;
+        use rand::Rng;
+
+        #[test]
+        fn test_matrix_vector_mul() {
+            let mut a = [[[U16!(0); SABER_N]; SABER_L_MAX]; SABER_L];
+            let mut s: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
+            let mut a_c: [[[u16; SABER_N]; SABER_L]; SABER_L] = [[[0u16; SABER_N]; SABER_L]; SABER_L];
+            let mut s_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            let mut res1: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            let mut res2: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
+            let transpose: i16 = 0;
+
+            let mut rng = rand::thread_rng();
 
-        for i in 0..SABER_L {
-            for j in 0..SABER_N {
-                let b_x: u16 = rng.gen_range(0..8192);
-                b[i][j] = U16!(b_x);
-                b_c[i][j] = b_x;
+            for i in 0..SABER_L {
+                for j in 0..SABER_L {
+                    for x in 0..SABER_N {
+                        let a_x: u16 = rng.gen_range(0..8192);
+                        a[i][j][x] = U16!(a_x);
+                        a_c[i][j][x] = a_x;
+                    }
+                }
             }
-        }
-        for i in 0..SABER_L {
-            for j in 0..SABER_N {
-                let s_x: u16 = rng.gen_range(0..8192);
-                s[i][j] = U16!(s_x);
-                s_c[i][j] = s_x;
+            for i in 0..SABER_L {
+                for j in 0..SABER_N {
+                    let s_x: u16 = rng.gen_range(0..8192);
+                    s[i][j] = U16!(s_x);
+                    s_c[i][j] = s_x;
+                }
             }
-        }
 
-        unsafe { InnerProd(&mut b_c, &mut s_c, &mut res1) };
-        inner_prod(b, s, &mut res2);
+            unsafe { MatrixVectorMul(&a_c, &s_c, &mut res1, transpose) };
+            matrix_vector_mul::<CRefParams>(&a, &s, &mut res2, transpose == 1);
 
-        let mut check = [0u16; SABER_N];
-        wrappedu162u16(&mut check[..], &res2[..]);
-        assert_eq!(res1, check);
-    }
+            let mut check: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            for i in 0..SABER_L {
+                wrappedu162u16(&mut check[i][..], &res2[i][..]);
+            }
+            assert_eq!(res1, check);
+            unsafe { MatrixVectorMul(&a_c, &s_c, &mut res1, 1) };
+            matrix_vector_mul::<CRefParams>(&a, &s, &mut res2, true);
 
-    #[test]
-    fn test_gen_matrix() {
-        let mut a1 = [[[0u16; SABER_N]; SABER_L]; SABER_L];
-        let mut a2 = [[[U16!(0); SABER_N]; SABER_L]; SABER_L];
-        let mut rng = rand::thread_rng();
-        let mut seed = [0u8; SABER_SEEDBYTES];
-        for i in 0..SABER_SEEDBYTES {
-            seed[i] = rng.gen();
+            let mut check: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            for i in 0..SABER_L {
+                wrappedu162u16(&mut check[i][..], &res2[i][..]);
+            }
+            assert_eq!(res1, check);
         }
-        unsafe { GenMatrix(&mut a1, &mut seed) };
-        gen_matrix(&mut a2, seed).expect("gen_matrix failed!");
 
-        let mut check = [[[0u16; SABER_N]; SABER_L]; SABER_L];
-        for i in 0..SABER_L {
-            for j in 0..SABER_L {
-                wrappedu162u16(&mut check[i][j][..], &a2[i][j][..]);
+        #[test]
+        fn test_inner_prod() {
+            let mut b: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
+            let mut s: [[U16; SABER_N]; SABER_L] = [[U16!(0); SABER_N]; SABER_L];
+            let mut b_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            let mut s_c: [[u16; SABER_N]; SABER_L] = [[0u16; SABER_N]; SABER_L];
+            let mut res1: [u16; SABER_N] = [0u16; SABER_N];
+            let mut res2: [U16; SABER_N] = [U16!(0); SABER_N];
+
+            let mut rng = rand::thread_rng();
+
+            for i in 0..SABER_L {
+                for j in 0..SABER_N {
+                    let b_x: u16 = rng.gen_range(0..8192);
+                    b[i][j] = U16!(b_x);
+                    b_c[i][j] = b_x;
+                }
             }
+            for i in 0..SABER_L {
+                for j in 0..SABER_N {
+                    let s_x: u16 = rng.gen_range(0..8192);
+                    s[i][j] = U16!(s_x);
+                    s_c[i][j] = s_x;
+                }
+            }
+
+            unsafe { InnerProd(&b_c, &s_c, &mut res1) };
+            inner_prod::<CRefParams>(&b, &s, &mut res2);
+
+            let mut check = [0u16; SABER_N];
+            wrappedu162u16(&mut check[..], &res2[..]);
+            assert_eq!(res1, check);
         }
-        assert_eq!(a1, check);
-    }
 
-    #[test]
-    fn test_gen_secret() {
-        let mut s1 = [[0u16; SABER_N]; SABER_L];
-        let mut s2 = [[U16!(0); SABER_N]; SABER_L];
-        let mut rng = rand::thread_rng();
-        let mut seed = [0u8; SABER_SEEDBYTES];
-        for i in 0..SABER_SEEDBYTES {
-            seed[i] = rng.gen();
+        #[test]
+        fn test_gen_matrix() {
+            let mut a1 = [[[0u16; SABER_N]; SABER_L]; SABER_L];
+            let mut a2 = [[[U16!(0); SABER_N]; SABER_L_MAX]; SABER_L];
+            let mut rng = rand::thread_rng();
+            let mut seed = [0u8; SABER_SEEDBYTES];
+            rng.fill(&mut seed[..]);
+            unsafe { GenMatrix(&mut a1, &seed) };
+            gen_matrix::<CRefParams>(&mut a2, seed);
+
+            let mut check = [[[0u16; SABER_N]; SABER_L]; SABER_L];
+            for i in 0..SABER_L {
+                for j in 0..SABER_L {
+                    wrappedu162u16(&mut check[i][j][..], &a2[i][j][..]);
+                }
+            }
+            assert_eq!(a1, check);
         }
-        unsafe { GenSecret(&mut s1, &mut seed) };
-        gen_secret(&mut s2, seed).expect("gen_secret failed!");
 
-        let mut check = [[0u16; SABER_N]; SABER_L];
-        for i in 0..SABER_L {
-            wrappedu162u16(&mut check[i][..], &s2[i][..]);
+        #[test]
+        fn test_gen_secret() {
+            let mut s1 = [[0u16; SABER_N]; SABER_L];
+            let mut s2 = [[U16!(0); SABER_N]; SABER_L];
+            let mut rng = rand::thread_rng();
+            let mut seed = [0u8; SABER_SEEDBYTES];
+            rng.fill(&mut seed[..]);
+            unsafe { GenSecret(&mut s1, &seed) };
+            gen_secret::<CRefParams>(&mut s2, &seed);
+
+            let mut check = [[0u16; SABER_N]; SABER_L];
+            for i in 0..SABER_L {
+                wrappedu162u16(&mut check[i][..], &s2[i][..]);
+            }
+            assert_eq!(s1, check);
         }
-        assert_eq!(s1, check);
     }
 }
@@ -1,6 +1,7 @@
 use crate::saber_params::{SABER_N, U16};
+use crate::wipe::wipe;
 use crate::U16;
-use std::num::Wrapping;
+use core::num::Wrapping;
 
 const N_SB: usize = SABER_N >> 2;
 const N_SB_RES: usize = 2 * N_SB - 1;
@@ -12,7 +13,7 @@ fn wrapping_mul(x: U16, y: U16) -> U16 {
 
 /// Apply Karatsuba multiplication to `a_1` and `b_1`
 /// to compute `result_final`.
-fn karatsuba_simple(a_1: [U16; N_SB], b_1: [U16; N_SB], result_final: &mut [U16]) {
+fn karatsuba_simple(a_1: &[U16; N_SB], b_1: &[U16; N_SB], result_final: &mut [U16]) {
     const KARATSUBA_N: usize = 64;
 
     let mut d01 = [U16!(0); KARATSUBA_N / 2 - 1];
@@ -87,6 +88,7 @@ fn karatsuba_simple(a_1: [U16; N_SB], b_1: [U16; N_SB], result_final: &mut [U16]
     for i in 0..KARATSUBA_N - 1 {
         result_final[i + KARATSUBA_N / 2] += result_d01[i];
     }
+    wipe!(d01, d0123, d23, result_d01);
 }
 
 /// Apply Toom-Cook multiplication with k=4.
@@ -95,7 +97,7 @@ fn karatsuba_simple(a_1: [U16; N_SB], b_1: [U16; N_SB], result_final: &mut [U16]
 /// of the convolution. `k` stands for the parameter defining into how many chunks
 /// both arguments are split. For resulting smaller integers, Karatsuba multiplication
 /// is applied as part of this implementation.
-fn toom_cook_4way(a: [U16; SABER_N], b: [U16; SABER_N], result: &mut [U16]) {
+fn toom_cook_4way(a: &[U16; SABER_N], b: &[U16; SABER_N], result: &mut [U16]) {
     let (inv3, inv9, inv15) = (U16!(43691), U16!(36409), U16!(61167));
 
     let mut aw1 = [U16!(0); N_SB];
@@ -181,13 +183,13 @@ fn toom_cook_4way(a: [U16; SABER_N], b: [U16; SABER_N], result: &mut [U16]) {
         bw1[j] = r3;
     }
 
-    karatsuba_simple(aw1, bw1, &mut w1);
-    karatsuba_simple(aw2, bw2, &mut w2);
-    karatsuba_simple(aw3, bw3, &mut w3);
-    karatsuba_simple(aw4, bw4, &mut w4);
-    karatsuba_simple(aw5, bw5, &mut w5);
-    karatsuba_simple(aw6, bw6, &mut w6);
-    karatsuba_simple(aw7, bw7, &mut w7);
+    karatsuba_simple(&aw1, &bw1, &mut w1);
+    karatsuba_simple(&aw2, &bw2, &mut w2);
+    karatsuba_simple(&aw3, &bw3, &mut w3);
+    karatsuba_simple(&aw4, &bw4, &mut w4);
+    karatsuba_simple(&aw5, &bw5, &mut w5);
+    karatsuba_simple(&aw6, &bw6, &mut w6);
+    karatsuba_simple(&aw7, &bw7, &mut w7);
 
     for i in 0..N_SB_RES {
         r0 = w1[i];
@@ -243,13 +245,15 @@ fn toom_cook_4way(a: [U16; SABER_N], b: [U16; SABER_N], result: &mut [U16]) {
         c[i + 320] += r1;
         c[i + 384] += r0;
     }
+    wipe!(bw1, bw2, bw3, bw4, bw5, bw6, bw7);
+    wipe!(w1, w2, w3, w4, w5, w6, w7);
 }
 
 /// Compute polynomial multiplication accumulated.
 ///
 /// Consider `a` and `b` as polynomials and compute the multiplication
 /// of both polynomials. The result is added to `res`.
-pub fn poly_mul_acc(a: [U16; SABER_N], b: [U16; SABER_N], res: &mut [U16; SABER_N]) {
+pub fn poly_mul_acc(a: &[U16; SABER_N], b: &[U16; SABER_N], res: &mut [U16; SABER_N]) {
     let mut c = [U16!(0); 2 * SABER_N];
 
     // convolution
@@ -259,43 +263,181 @@ pub fn poly_mul_acc(a: [U16; SABER_N], b: [U16; SABER_N], res: &mut [U16; SABER_
     for i in SABER_N..2 * SABER_N {
         res[i - SABER_N] = res[i - SABER_N] + c[i - SABER_N] - c[i];
     }
+    wipe!(c);
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::link_c_reference::poly_mul_acc as poly_mul_acc_c;
-    use crate::poly_mul::*;
-    use crate::saber_params::wrappedu162u16;
+mod oracle_tests {
+    use crate::oracle::{self, random_poly};
+    use crate::poly_mul::poly_mul_acc;
+    use crate::saber_params::{SABER_EQ, SABER_N, U16};
     use crate::U16;
-    use std::num::Wrapping;
+    use core::num::Wrapping;
+
+    /// Compare `a` and `b` modulo q = 2^SABER_EQ.
+    /// Toom-Cook divides by powers of two and is not exact in the upper bits.
+    fn assert_eq_mod_q(a: &[U16; SABER_N], b: &[U16; SABER_N]) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert_eq!(x.0 % (1 << SABER_EQ), y.0 % (1 << SABER_EQ));
+        }
+    }
 
     #[test]
-    fn test_poly_mul() {
-        use rand::Rng;
+    fn test_poly_mul_schoolbook() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..16 {
+            let a = random_poly(&mut rng, SABER_EQ);
+            let b = random_poly(&mut rng, SABER_EQ);
+            let acc = random_poly(&mut rng, 16);
+
+            let mut res = acc;
+            poly_mul_acc(&a, &b, &mut res);
+            let mut expected = acc;
+            oracle::poly_mul_acc(&a, &b, &mut expected);
+            assert_eq_mod_q(&res, &expected);
+        }
+    }
 
+    #[test]
+    fn test_poly_mul_small_signed() {
+        // secrets have small coefficients of both signs
         let mut rng = rand::thread_rng();
-        let mut a = [U16!(0); 256];
-        let mut b = [U16!(0); 256];
-        let mut a_c = [0u16; 256];
-        let mut b_c = [0u16; 256];
-
-        for i in 0..256 {
-            let a_x: u16 = rng.gen_range(0..8192);
-            let b_x: u16 = rng.gen_range(0..8192);
-            a[i] = U16!(a_x);
-            b[i] = U16!(b_x);
-            a_c[i] = a_x;
-            b_c[i] = b_x;
+        let a = random_poly(&mut rng, SABER_EQ);
+        let mut s = random_poly(&mut rng, 3);
+        for coefficient in s.iter_mut() {
+            *coefficient -= U16!(4);
+        }
+
+        let mut res = [U16!(0); SABER_N];
+        poly_mul_acc(&a, &s, &mut res);
+        let mut expected = [U16!(0); SABER_N];
+        oracle::poly_mul_acc(&a, &s, &mut expected);
+        assert_eq_mod_q(&res, &expected);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use crate::poly_mul::poly_mul_acc;
+    use crate::saber_params::{SABER_EQ, SABER_N, U16};
+    use crate::U16;
+    use core::num::Wrapping;
+    use proptest::prelude::*;
+
+    const Q: u16 = 1 << SABER_EQ;
+
+    /// Polynomial with coefficients in `[0, q)`
+    fn poly() -> impl Strategy<Value = [U16; SABER_N]> {
+        prop::collection::vec(0..Q, SABER_N).prop_map(|v| {
+            let mut a = [U16!(0); SABER_N];
+            for (x, y) in a.iter_mut().zip(v) {
+                *x = U16!(y);
+            }
+            a
+        })
+    }
+
+    fn mod_q(a: [U16; SABER_N]) -> [U16; SABER_N] {
+        a.map(|x| x & U16!(Q - 1))
+    }
+
+    fn add(a: [U16; SABER_N], b: [U16; SABER_N]) -> [U16; SABER_N] {
+        let mut c = a;
+        for (x, y) in c.iter_mut().zip(b) {
+            *x += y;
+        }
+        c
+    }
+
+    fn mul(a: [U16; SABER_N], b: [U16; SABER_N]) -> [U16; SABER_N] {
+        let mut res = [U16!(0); SABER_N];
+        poly_mul_acc(&a, &b, &mut res);
+        res
+    }
+
+    /// Multiply `a` by X in Z[X]/(X^N + 1)
+    fn mul_x(a: [U16; SABER_N]) -> [U16; SABER_N] {
+        let mut b = [U16!(0); SABER_N];
+        b[0] = -a[SABER_N - 1];
+        b[1..].copy_from_slice(&a[..SABER_N - 1]);
+        b
+    }
+
+    proptest! {
+        #[test]
+        fn test_accumulates(a in poly(), b in poly(), acc in poly()) {
+            let mut res = acc;
+            poly_mul_acc(&a, &b, &mut res);
+            prop_assert_eq!(mod_q(res), mod_q(add(acc, mul(a, b))));
         }
-        let mut res = [0u16; 256];
-        let mut res2 = [U16!(0); 256];
 
-        unsafe { poly_mul_acc_c(&mut a_c, &mut b_c, &mut res) };
+        #[test]
+        fn test_bilinear(a in poly(), a2 in poly(), b in poly(), c in 0..Q) {
+            prop_assert_eq!(
+                mod_q(mul(mod_q(add(a, a2)), b)),
+                mod_q(add(mul(a, b), mul(a2, b)))
+            );
+            prop_assert_eq!(
+                mod_q(mul(b, mod_q(add(a, a2)))),
+                mod_q(add(mul(b, a), mul(b, a2)))
+            );
+            prop_assert_eq!(
+                mod_q(mul(mod_q(a.map(|x| x * U16!(c))), b)),
+                mod_q(mul(a, b).map(|x| x * U16!(c)))
+            );
+        }
 
-        poly_mul_acc(a, b, &mut res2);
+        #[test]
+        fn test_negacyclic(a in poly(), b in poly()) {
+            prop_assert_eq!(mod_q(mul(mod_q(mul_x(a)), b)), mod_q(mul_x(mul(a, b))));
+            // X^N = -1
+            let mut x_n1 = [U16!(0); SABER_N];
+            x_n1[SABER_N - 1] = U16!(1);
+            prop_assert_eq!(
+                mod_q(mul(mul_x(x_n1), a)),
+                mod_q(a.map(|x| -x))
+            );
+        }
+    }
+}
 
-        let mut check = [0u16; 256];
-        wrappedu162u16(&mut check[..], &res2[..]);
-        assert_eq!(res, check);
+#[cfg(all(test, feature = "cref"))]
+mod tests {
+    crate::link_c_reference::for_each_variant! {
+        use cref::poly_mul_acc as poly_mul_acc_c;
+        use crate::poly_mul::*;
+        use crate::saber_params::wrappedu162u16;
+        use crate::U16;
+        use core::num::Wrapping;
+
+        #[test]
+        fn test_poly_mul() {
+            use rand::Rng;
+
+            let mut rng = rand::thread_rng();
+            let mut a = [U16!(0); 256];
+            let mut b = [U16!(0); 256];
+            let mut a_c = [0u16; 256];
+            let mut b_c = [0u16; 256];
+
+            for i in 0..256 {
+                let a_x: u16 = rng.gen_range(0..8192);
+                let b_x: u16 = rng.gen_range(0..8192);
+                a[i] = U16!(a_x);
+                b[i] = U16!(b_x);
+                a_c[i] = a_x;
+                b_c[i] = b_x;
+            }
+            let mut res = [0u16; 256];
+            let mut res2 = [U16!(0); 256];
+
+            unsafe { poly_mul_acc_c(&mut a_c, &mut b_c, &mut res) };
+
+            poly_mul_acc(&a, &b, &mut res2);
+
+            let mut check = [0u16; 256];
+            wrappedu162u16(&mut check[..], &res2[..]);
+            assert_eq!(res, check);
+        }
     }
 }
@@ -4,152 +4,522 @@
 //! The implementation follows the design discussed in this blogpost:
 //! <https://lukas-prokop.at/articles/2021-12-31-nists-rng-in-rust>
 
+use crate::classify::poison;
+#[cfg(feature = "serde")]
+use crate::serialization::ByteArray;
+use crate::wipe::wipe;
+use crate::Error;
 use aes::BlockEncrypt;
 use aes::NewBlockCipher;
-use std::error;
-use std::fmt;
+use aes::{Aes256, ParBlocks};
+use core::fmt;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 
 /// Trait requiring primitives to generate pseudo-random numbers.
-/// `AesState` is an object implementing this trait.
+/// It is implemented for every cryptographically secure `rand_core` generator,
+/// i.e. every `RngCore + CryptoRng` including `AesState`.
 pub trait RNGState {
     /// Fill the buffer `x` with pseudo-random bytes resulting from the
     /// RNG run updating the RNG state
-    fn randombytes(&mut self, x: &mut [u8]) -> Result<(), Box<dyn error::Error>>;
-    /// Initialize/reset the RNG state based on the seed provided as `entropy_input`
-    fn randombytes_init(&mut self, entropy_input: [u8; 48]);
+    fn randombytes(&mut self, x: &mut [u8]) -> Result<(), Error>;
+}
+
+impl<R: RngCore + CryptoRng + ?Sized> RNGState for R {
+    fn randombytes(&mut self, x: &mut [u8]) -> Result<(), Error> {
+        self.try_fill_bytes(x).map_err(Error::from)
+    }
+}
+
+/// Seed of `AesState` as `SeedableRng`, i.e. the 48 bytes of `entropy_input`.
+/// `rand_core` requires a `Default` seed, which `[u8; 48]` does not provide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AesSeed(pub [u8; 48]);
+
+impl Default for AesSeed {
+    fn default() -> Self {
+        AesSeed([0u8; 48])
+    }
+}
+
+impl AsRef<[u8]> for AesSeed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for AesSeed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl From<[u8; 48]> for AesSeed {
+    fn from(entropy_input: [u8; 48]) -> Self {
+        AesSeed(entropy_input)
+    }
 }
 
 /// AesState is a struct storing data of a pseudo-random number generator.
 /// Using `randombytes_init`, it can be initialized once. Using `randombytes`,
 /// one can successively fetch new pseudo-random numbers.
-#[derive(Clone, Debug, PartialEq)]
+/// It is the `CtrDrbg` of the NIST PQC framework and reproduces its KAT files.
+#[derive(Clone)]
 pub struct AesState {
     pub key: [u8; 32],
     pub v: [u8; 16],
     pub reseed_counter: i32,
+    /// key schedule of `cipher_key`
+    cipher: Aes256,
+    /// key the cached key schedule was expanded from; differs from `key`
+    /// only if `key` was assigned directly
+    cipher_key: [u8; 32],
 }
 
 impl AesState {
-    /// Returns a fresh RNG state
+    /// Returns a fresh RNG state with an all-zero key.
+    /// It must be seeded with `randombytes_init` before use;
+    /// prefer `from_os_entropy` for production use.
     pub fn new() -> AesState {
         AesState {
             key: [0; 32],
             v: [0; 16],
             reseed_counter: 0,
+            cipher: Self::expand(&[0; 32]),
+            cipher_key: [0; 32],
         }
     }
 
     /// Returns an RNG state which is initialized with a seed
     /// of bytes `[0, 1, 2, 3, 4, …, 47]` which is common in the NIST framework
     pub fn with_increasing_seed() -> AesState {
-        let mut state = AesState {
-            key: [0; 32],
-            v: [0; 16],
-            reseed_counter: 0,
-        };
+        let mut state = AesState::new();
         let mut entropy = [0u8; 48];
-        for i in 0..48 {
-            entropy[i] = i as u8;
+        for (i, e) in entropy.iter_mut().enumerate() {
+            *e = i as u8;
         }
         state.randombytes_init(entropy);
         state
     }
 
-    /// This runs AES256 in ECB mode. Here `key` is a 256-bit AES key,
-    /// `ctr` is a 128-bit plaintext value and `buffer` is a 128-bit
-    /// ciphertext value.
-    fn aes256_ecb(key: &[u8; 32], ctr: &[u8; 16], buffer: &mut [u8; 16]) {
-        let cipher = aes::Aes256::new(key.into());
-        buffer.copy_from_slice(ctr);
-        cipher.encrypt_block(buffer.into());
+    /// Returns an RNG state which is initialized with a seed
+    /// of 48 bytes read from the entropy source of the operating system.
+    /// Fails with `Error::RngFailure` if the operating system cannot provide them.
+    #[cfg(feature = "getrandom")]
+    pub fn from_os_entropy() -> Result<AesState, Error> {
+        let mut entropy = [0u8; 48];
+        getrandom::getrandom(&mut entropy).map_err(|_| Error::RngFailure)?;
+        let mut state = AesState::new();
+        state.randombytes_init(entropy);
+        wipe!(entropy);
+        Ok(state)
+    }
+
+    /// Expands the 256-bit AES `key` into its key schedule
+    fn expand(key: &[u8; 32]) -> Aes256 {
+        Aes256::new(key.into())
     }
 
-    /// Update `key` and `v` with `provided_data` by running one round of AES in counter mode
+    /// Increment the 128-bit big-endian counter `v` modulo 2^128
+    fn increment(v: &mut [u8; 16]) {
+        let count = u128::from_be_bytes(*v);
+        v.copy_from_slice(&count.wrapping_add(1).to_be_bytes());
+    }
+
+    /// Fill the buffer `x` with the AES encryptions of the successors of counter `v`.
+    /// The counter blocks are encrypted in batches of `ParBlocks` in parallel.
+    fn aes256_ctr_generate(cipher: &Aes256, v: &mut [u8; 16], x: &mut [u8]) {
+        let mut blocks = ParBlocks::default();
+        for batch in x.chunks_mut(16 * blocks.len()) {
+            let count = batch.len().div_ceil(16);
+            for block in blocks.iter_mut().take(count) {
+                Self::increment(v);
+                block.copy_from_slice(v);
+            }
+
+            cipher.encrypt_par_blocks(&mut blocks);
+
+            for (chunk, block) in batch.chunks_mut(16).zip(blocks.iter()) {
+                chunk.copy_from_slice(&block[..chunk.len()]);
+            }
+        }
+    }
+
+    /// Update `key` and `v` with `provided_data` by running one round of AES in counter mode.
+    /// `cipher` is the key schedule of `key` and is rebuilt for the new key.
     fn aes256_ctr_update(
         provided_data: &mut Option<[u8; 48]>,
+        cipher: &mut Aes256,
         key: &mut [u8; 32],
         v: &mut [u8; 16],
     ) {
-        let mut temp = [[0u8; 16]; 3];
-
-        for tmp in &mut temp[0..3] {
-            let count = u128::from_be_bytes(*v);
-            v.copy_from_slice(&(count + 1).to_be_bytes());
-
-            Self::aes256_ecb(key, v, tmp);
-        }
+        let mut temp = [0u8; 48];
+        Self::aes256_ctr_generate(cipher, v, &mut temp);
 
         if let Some(d) = provided_data {
-            for j in 0..3 {
-                for i in 0..16 {
-                    temp[j][i] ^= d[16 * j + i];
-                }
+            for (t, d) in temp.iter_mut().zip(d.iter()) {
+                *t ^= d;
             }
         }
 
-        key[0..16].copy_from_slice(&temp[0]);
-        key[16..32].copy_from_slice(&temp[1]);
-        v.copy_from_slice(&temp[2]);
+        key.copy_from_slice(&temp[0..32]);
+        v.copy_from_slice(&temp[32..48]);
+        *cipher = Self::expand(key);
+        wipe!(temp);
     }
 }
 
-impl RNGState for AesState {
+impl AesState {
     /// Fill the buffer `x` with pseudo-random bytes resulting from the
     /// AES run in counter mode updating the object state
-    fn randombytes(&mut self, x: &mut [u8]) -> Result<(), Box<dyn error::Error>> {
-        for chunk in x.chunks_mut(16) {
-            let count = u128::from_be_bytes(self.v);
-            self.v.copy_from_slice(&(count + 1).to_be_bytes());
-
-            let mut block = [0u8; 16];
-            Self::aes256_ecb(&self.key, &self.v, &mut block);
-
-            (*chunk).copy_from_slice(&block[..chunk.len()]);
+    fn generate(&mut self, x: &mut [u8]) {
+        if self.cipher_key != self.key {
+            self.cipher = Self::expand(&self.key);
         }
-
-        Self::aes256_ctr_update(&mut None, &mut self.key, &mut self.v);
+        Self::aes256_ctr_generate(&self.cipher, &mut self.v, x);
+        poison!(x);
+        Self::aes256_ctr_update(&mut None, &mut self.cipher, &mut self.key, &mut self.v);
+        self.cipher_key = self.key;
         self.reseed_counter += 1;
-
-        Ok(())
     }
 
     /// Initialize/reset the state based on the seed provided as `entropy_input`
-    fn randombytes_init(&mut self, entropy_input: [u8; 48]) {
+    pub fn randombytes_init(&mut self, entropy_input: [u8; 48]) {
         self.key = [0u8; 32];
         self.v = [0u8; 16];
+        self.cipher = Self::expand(&self.key);
         self.reseed_counter = 1i32;
 
-        Self::aes256_ctr_update(&mut Some(entropy_input), &mut self.key, &mut self.v);
+        Self::aes256_ctr_update(
+            &mut Some(entropy_input),
+            &mut self.cipher,
+            &mut self.key,
+            &mut self.v,
+        );
+        self.cipher_key = self.key;
         self.reseed_counter = 1;
     }
 }
 
+/// Every call of `fill_bytes` corresponds to one call of `randombytes`
+/// of the NIST framework. `next_u32` and `next_u64` consume one call each.
+impl RngCore for AesState {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.generate(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for AesState {}
+
+impl SeedableRng for AesState {
+    type Seed = AesSeed;
+
+    /// Initialize the state like `randombytes_init` with `seed` as `entropy_input`
+    fn from_seed(seed: AesSeed) -> Self {
+        let mut state = AesState::new();
+        state.randombytes_init(seed.0);
+        state
+    }
+}
+
 impl Default for AesState {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl PartialEq for AesState {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.v == other.v && self.reseed_counter == other.reseed_counter
+    }
+}
+
 impl Eq for AesState {}
 
+/// Omits the internal state `key` and `v`
+impl fmt::Debug for AesState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AesState")
+            .field("reseed_counter", &self.reseed_counter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Omits the internal state `key` and `v`
 impl fmt::Display for AesState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "AesState {{")?;
-        writeln!(f, "  key = {:?}", self.key)?;
-        writeln!(f, "  v   = {:?}", self.v)?;
         writeln!(f, "  reseed_counter = {}", self.reseed_counter)?;
+        writeln!(f, "  ..")?;
         writeln!(f, "}}")
     }
 }
 
+/// Serialized form of `AesState`; the key schedule is expanded again on deserialization
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "AesState")]
+struct AesStateRepr {
+    key: ByteArray<32>,
+    v: ByteArray<16>,
+    reseed_counter: i32,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AesState {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AesStateRepr {
+            key: ByteArray(self.key),
+            v: ByteArray(self.v),
+            reseed_counter: self.reseed_counter,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AesState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = AesStateRepr::deserialize(deserializer)?;
+        Ok(AesState {
+            key: repr.key.0,
+            v: repr.v.0,
+            reseed_counter: repr.reseed_counter,
+            cipher: AesState::expand(&repr.key.0),
+            cipher_key: repr.key.0,
+        })
+    }
+}
+
+/// Resets the state to the one of `AesState::new`. The key schedule
+/// is overwritten with the one of the all-zero key.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for AesState {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+        self.reseed_counter.zeroize();
+        self.cipher_key.zeroize();
+        self.cipher = Self::expand(&[0; 32]);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for AesState {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for AesState {}
+
+/// Length of the seed material of the CTR_DRBG in bytes (seedlen)
+pub const CTR_DRBG_SEEDLEN: usize = 48;
+/// Maximum number of bytes returned by one `CtrDrbg::generate` call (2^19 bits)
+pub const CTR_DRBG_MAX_REQUEST_BYTES: usize = 1 << 16;
+/// Maximum number of `CtrDrbg::generate` calls between two reseeds
+pub const CTR_DRBG_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// CTR_DRBG of NIST SP 800-90A Rev. 1 using AES-256 without derivation function.
+///
+/// Prediction resistance is not supported. The personalization string and the
+/// additional inputs are at most `CTR_DRBG_SEEDLEN` bytes long and padded with zeros.
+/// `AesState` is the special case used by the NIST PQC framework:
+/// no personalization string, no additional input and no reseed limit.
+#[derive(Clone)]
+pub struct CtrDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+    reseed_counter: u64,
+    /// key schedule of `key`
+    cipher: Aes256,
+}
+
+impl CtrDrbg {
+    /// Instantiate the DRBG with full-entropy `entropy_input` and an optional `personalization_string`
+    pub fn instantiate(
+        entropy_input: &[u8; CTR_DRBG_SEEDLEN],
+        personalization_string: &[u8],
+    ) -> Result<CtrDrbg, Error> {
+        let mut drbg = CtrDrbg {
+            key: [0u8; 32],
+            v: [0u8; 16],
+            reseed_counter: 0,
+            cipher: AesState::expand(&[0u8; 32]),
+        };
+        drbg.reseed(entropy_input, personalization_string)?;
+        Ok(drbg)
+    }
+
+    /// Reseed the DRBG with fresh `entropy_input` and an optional `additional_input`
+    pub fn reseed(
+        &mut self,
+        entropy_input: &[u8; CTR_DRBG_SEEDLEN],
+        additional_input: &[u8],
+    ) -> Result<(), Error> {
+        let mut seed_material = Self::pad(additional_input)?;
+        for (s, e) in seed_material.iter_mut().zip(entropy_input.iter()) {
+            *s ^= e;
+        }
+        let mut provided_data = Some(seed_material);
+        AesState::aes256_ctr_update(
+            &mut provided_data,
+            &mut self.cipher,
+            &mut self.key,
+            &mut self.v,
+        );
+        self.reseed_counter = 1;
+        wipe!(seed_material, provided_data);
+        Ok(())
+    }
+
+    /// Fill `x` with pseudo-random bytes, mixing in an optional `additional_input`.
+    ///
+    /// Fails with `Error::ReseedRequired` once `CTR_DRBG_RESEED_INTERVAL` requests
+    /// have been served since the last (re)seed and with `Error::InvalidLength`
+    /// if `x` is longer than `CTR_DRBG_MAX_REQUEST_BYTES`.
+    pub fn generate(&mut self, x: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if x.len() > CTR_DRBG_MAX_REQUEST_BYTES {
+            return Err(Error::InvalidLength {
+                expected: CTR_DRBG_MAX_REQUEST_BYTES,
+                got: x.len(),
+            });
+        }
+        if self.reseed_counter > CTR_DRBG_RESEED_INTERVAL {
+            return Err(Error::ReseedRequired);
+        }
+
+        let mut provided_data = if additional_input.is_empty() {
+            None
+        } else {
+            let padded = Self::pad(additional_input)?;
+            Some(padded)
+        };
+        if provided_data.is_some() {
+            AesState::aes256_ctr_update(
+                &mut provided_data,
+                &mut self.cipher,
+                &mut self.key,
+                &mut self.v,
+            );
+        }
+
+        AesState::aes256_ctr_generate(&self.cipher, &mut self.v, x);
+        AesState::aes256_ctr_update(
+            &mut provided_data,
+            &mut self.cipher,
+            &mut self.key,
+            &mut self.v,
+        );
+        self.reseed_counter += 1;
+        wipe!(provided_data);
+        Ok(())
+    }
+
+    /// Returns the number of requests since the last (re)seed plus one
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    /// Pad `input` with zeros to `CTR_DRBG_SEEDLEN` bytes
+    fn pad(input: &[u8]) -> Result<[u8; CTR_DRBG_SEEDLEN], Error> {
+        if input.len() > CTR_DRBG_SEEDLEN {
+            return Err(Error::InvalidLength {
+                expected: CTR_DRBG_SEEDLEN,
+                got: input.len(),
+            });
+        }
+        let mut padded = [0u8; CTR_DRBG_SEEDLEN];
+        padded[..input.len()].copy_from_slice(input);
+        Ok(padded)
+    }
+}
+
+/// Every `fill_bytes` call is split into requests of at most
+/// `CTR_DRBG_MAX_REQUEST_BYTES` without additional input.
+impl RngCore for CtrDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    /// Panics if the DRBG must be reseeded
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("CTR_DRBG must be reseeded")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        for chunk in dest.chunks_mut(CTR_DRBG_MAX_REQUEST_BYTES) {
+            self.generate(chunk, &[])?;
+        }
+        Ok(())
+    }
+}
+
+impl CryptoRng for CtrDrbg {}
+
+/// Omits the internal state `key` and `v`
+impl fmt::Debug for CtrDrbg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CtrDrbg")
+            .field("reseed_counter", &self.reseed_counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for CtrDrbg {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.v == other.v && self.reseed_counter == other.reseed_counter
+    }
+}
+
+impl Eq for CtrDrbg {}
+
+/// Resets key, counter and reseed counter to zero. The DRBG must be
+/// instantiated again before further use.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for CtrDrbg {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+        self.reseed_counter.zeroize();
+        self.cipher = AesState::expand(&[0; 32]);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for CtrDrbg {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::link_c_reference::initialize_c_randombytes;
-    use crate::link_c_reference::randombytes as randombytes_c;
 
     #[test]
-    fn test_rng() -> Result<(), Box<dyn error::Error>> {
+    fn test_rng() -> Result<(), Error> {
         let mut data = [0u8; 256];
         let mut rng_state = AesState::with_increasing_seed();
 
@@ -205,7 +575,271 @@ mod tests {
     }
 
     #[test]
+    fn test_assigned_key() -> Result<(), Error> {
+        let mut rng_state = AesState::with_increasing_seed();
+        let mut reference = AesState::new();
+        reference.randombytes_init([7u8; 48]);
+
+        rng_state.key = reference.key;
+        rng_state.v = reference.v;
+
+        let mut data1 = [0u8; 200];
+        let mut data2 = [0u8; 200];
+        rng_state.randombytes(&mut data1)?;
+        reference.randombytes(&mut data2)?;
+        assert_eq!(data1, data2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rand_core() -> Result<(), Error> {
+        let mut entropy = [0u8; 48];
+        for (i, e) in entropy.iter_mut().enumerate() {
+            *e = i as u8;
+        }
+        let mut seeded = AesState::from_seed(entropy.into());
+        let mut reference = AesState::with_increasing_seed();
+        assert_eq!(seeded, reference);
+
+        let mut data1 = [0u8; 100];
+        let mut data2 = [0u8; 100];
+        seeded.fill_bytes(&mut data1);
+        reference.randombytes(&mut data2)?;
+        assert_eq!(data1, data2);
+
+        let x = seeded.next_u64();
+        reference.randombytes(&mut data2[..8])?;
+        assert_eq!(x.to_le_bytes(), data2[..8]);
+        assert_eq!(seeded, reference);
+        Ok(())
+    }
+
+    /// Runs one CAVP test case: instantiate, reseed and two generate calls
+    /// of which the second returns the 512 bits to compare with
+    fn cavp_case(
+        entropy_input: &str,
+        personalization_string: &str,
+        entropy_input_reseed: &str,
+        additional_input_reseed: &str,
+        additional_input: [&str; 2],
+        returned_bits: &str,
+    ) -> Result<(), Error> {
+        fn hex(s: &str) -> [u8; 64] {
+            let mut out = [0u8; 64];
+            for (i, o) in out.iter_mut().take(s.len() / 2).enumerate() {
+                *o = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+            }
+            out
+        }
+        let seed = |s: &str| <[u8; 48]>::try_from(&hex(s)[..48]).unwrap();
+
+        let mut drbg = CtrDrbg::instantiate(
+            &seed(entropy_input),
+            &hex(personalization_string)[..personalization_string.len() / 2],
+        )?;
+        drbg.reseed(
+            &seed(entropy_input_reseed),
+            &hex(additional_input_reseed)[..additional_input_reseed.len() / 2],
+        )?;
+        let mut out = [0u8; 64];
+        for add in additional_input.iter() {
+            drbg.generate(&mut out, &hex(add)[..add.len() / 2])?;
+        }
+        assert_eq!(out, hex(returned_bits));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctr_drbg_vectors() -> Result<(), Error> {
+        // CAVP drbgvectors_pr_false/CTR_DRBG.rsp, [AES-256 no df], [PredictionResistance = False]
+        // (every case reseeds between instantiation and generation), COUNT = 0
+        cavp_case(
+            "e4bc23c5089a19d86f4119cb3fa08c0a4991e0a1def17e101e4c14d9c323460a7c2fb58e0b086c6c57b55f56cae25bad",
+            "",
+            "fd85a836bba85019881e8c6bad23c9061adc75477659acaea8e4a01dfe07a1832dad1c136f59d70f8653a5dc118663d6",
+            "",
+            ["", ""],
+            "b2cb8905c05e5950ca31895096be29ea3d5a3b82b269495554eb80fe07de43e193b9e7c3ece73b80e062b1c1f68202fbb1c52a040ea2478864295282234aaada",
+        )?;
+        // [AdditionalInputLen = 384], COUNT = 0
+        cavp_case(
+            "99903165903fea49c2db26ed675e44cc14cb2c1f28b836b203240b02771e831146ffc4335373bb344688c5c950670291",
+            "",
+            "b4ee99fa9e0eddaf4a3612013cd636c4af69177b43eebb3c58a305b9979b68b5cc820504f6c029aad78a5d29c66e84a0",
+            "2d8c5c28b05696e74774eb69a10f01c5fabc62691ddf7848a8004bb5eeb4d2c5febe1aa01f4d557b23d7e9a0e4e90655",
+            [
+                "0dc9cde42ac6e856f01a55f219c614de90c659260948db5053d414bab0ec2e13e995120c3eb5aafc25dc4bdcef8ace24",
+                "711be6c035013189f362211889248ca8a3268e63a7eb26836d915810a680ac4a33cd1180811a31a0f44f08db3dd64f91",
+            ],
+            "11c7a0326ea737baa7a993d510fafee5374e7bbe17ef0e3e29f50fa68aac2124b017d449768491cac06d136d691a4e80785739f9aaedf311bba752a3268cc531",
+        )?;
+        // [PersonalizationStringLen = 384], COUNT = 0
+        cavp_case(
+            "ffad10100025a879672ff50374b286712f457dd01441d76ac1a1cd15c7390dd93179a2f5920d198bf34a1b76fbc21289",
+            "1d2be6f25e88fa30c4ef42e4d54efd957dec231fa00143ca47580be666a8c143a916c90b3819a0a7ea914e3c9a2e7a3f",
+            "6c1a089cae313363bc76a780139eb4f2f2048b1f6b07896c5c412bff0385440fc43b73facbb79e3a252fa01fe17ab391",
+            "",
+            ["", ""],
+            "e053c7d4bd9099ef6a99f190a5fd80219437d642006672338da6e0fe73ca4d24ffa51151bfbdac78d8a2f6255046edf57a04626e9977139c6933274299f3bdff",
+        )?;
+        // [PersonalizationStringLen = 384], [AdditionalInputLen = 384], COUNT = 0
+        cavp_case(
+            "ae7ebe062971f5eb32e5b21444750785de816595ad2cbe80a209c8f8ab04b5468166de8c6ae522d8f10b56386a3b424f",
+            "55860dae57fcac297087c137efb796878a75868f6e7681114e9b73ed0c67e3c62bfc9f5d77e8caa59bcdb223f4ffd247",
+            "a42407931bfeca70e6ee5dd197021a129525051c07468e8b25587c5ad50abe9204e882fe847b8fd47cf7b4360e5aa034",
+            "ee4c88d1eb05f4853663eada501d2fc4b4984b283a88db579af2113031e03d9bc570de943dd168918f3ba8065581fea7",
+            [
+                "4b4b03ef19b0f259dca2b3ee3ae4cd86c3895a784b3d8eee043a2003c08289f8fffdad141e6b1ab2174d8d5d79c1e581",
+                "3062b33f116b46e20fe3c354726ae9b2a3a4c51922c8107863cb86f1f0bdad7554075659d91c371e2b11b1e8106a1ed5",
+            ],
+            "0d270518baeafac160ff1cb28c11ef68712c764c0c01674e6c9ca2cc9c7e0e8accfd3c753635ee070081eee7628af6187fbc2854b3c204461a796cf3f3fcb092",
+        )
+    }
+
+    #[test]
+    fn test_aes_state_debug_hides_state() {
+        let rng = AesState::with_increasing_seed();
+        assert_eq!(format!("{:?}", rng), "AesState { reseed_counter: 1, .. }");
+        assert_eq!(
+            rng.to_string(),
+            "AesState {\n  reseed_counter = 1\n  ..\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_ctr_drbg_debug_hides_state() -> Result<(), Error> {
+        let drbg = CtrDrbg::instantiate(&[0x5a; 48], &[])?;
+        let debug = format!("{:?}", drbg);
+        assert_eq!(debug, "CtrDrbg { reseed_counter: 1, .. }");
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctr_drbg_matches_aes_state() -> Result<(), Error> {
+        let mut entropy = [0u8; 48];
+        for (i, e) in entropy.iter_mut().enumerate() {
+            *e = i as u8;
+        }
+        let mut drbg = CtrDrbg::instantiate(&entropy, &[])?;
+        let mut aes_state = AesState::with_increasing_seed();
+
+        let mut data1 = [0u8; 100];
+        let mut data2 = [0u8; 100];
+        for _ in 0..3 {
+            drbg.generate(&mut data1, &[])?;
+            aes_state.randombytes(&mut data2)?;
+            assert_eq!(data1, data2);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctr_drbg_errors() -> Result<(), Error> {
+        let mut drbg = CtrDrbg::instantiate(&[0u8; 48], &[])?;
+        let mut data = [0u8; 32];
+        assert_eq!(
+            drbg.generate(&mut data, &[0u8; 49]),
+            Err(Error::InvalidLength {
+                expected: 48,
+                got: 49
+            })
+        );
+        assert!(drbg
+            .generate(&mut [0u8; CTR_DRBG_MAX_REQUEST_BYTES + 1], &[])
+            .is_err());
+
+        drbg.reseed_counter = CTR_DRBG_RESEED_INTERVAL;
+        drbg.generate(&mut data, &[])?;
+        assert_eq!(drbg.generate(&mut data, &[]), Err(Error::ReseedRequired));
+        assert_eq!(drbg.randombytes(&mut data), Err(Error::ReseedRequired));
+
+        drbg.reseed(&[1u8; 48], &[])?;
+        assert_eq!(drbg.reseed_counter(), 1);
+        drbg.generate(&mut data, &[])
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_zeroize() -> Result<(), Error> {
+        use zeroize::Zeroize;
+
+        let mut state = AesState::with_increasing_seed();
+        state.randombytes(&mut [0u8; 32])?;
+        state.zeroize();
+        assert_eq!(state, AesState::new());
+        assert_eq!(state.cipher_key, [0u8; 32]);
+
+        // the key schedule was reset as well
+        let mut data1 = [0u8; 32];
+        let mut data2 = [0u8; 32];
+        state.randombytes(&mut data1)?;
+        AesState::new().randombytes(&mut data2)?;
+        assert_eq!(data1, data2);
+
+        let mut drbg = CtrDrbg::instantiate(&[7u8; 48], b"zeroize")?;
+        drbg.zeroize();
+        assert_eq!(drbg.key, [0u8; 32]);
+        assert_eq!(drbg.v, [0u8; 16]);
+        assert_eq!(drbg.reseed_counter(), 0);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() -> Result<(), Error> {
+        let mut state = AesState::with_increasing_seed();
+        state.randombytes(&mut [0u8; 32])?;
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.starts_with(&format!(
+            "{{\"key\":\"{:02x}{:02x}",
+            state.key[0], state.key[1]
+        )));
+        let mut from_json: AesState = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, state);
+
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&state, &mut cbor).unwrap();
+        let mut from_cbor: AesState = ciborium::de::from_reader(&cbor[..]).unwrap();
+        assert_eq!(from_cbor, state);
+
+        // the restored states continue the sequence
+        let mut data = [[0u8; 32]; 3];
+        state.randombytes(&mut data[0])?;
+        from_json.randombytes(&mut data[1])?;
+        from_cbor.randombytes(&mut data[2])?;
+        assert_eq!(data[0], data[1]);
+        assert_eq!(data[0], data[2]);
+
+        // a key of 33 bytes is rejected
+        let too_long = json.replacen("\"key\":\"", "\"key\":\"00", 1);
+        assert!(serde_json::from_str::<AesState>(&too_long).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_from_os_entropy() -> Result<(), Error> {
+        let mut state1 = AesState::from_os_entropy()?;
+        let mut state2 = AesState::from_os_entropy()?;
+        assert_ne!(state1, AesState::new());
+        assert_ne!(state1, state2);
+        assert_eq!(state1.reseed_counter, 1);
+
+        let mut data1 = [0u8; 64];
+        let mut data2 = [0u8; 64];
+        state1.randombytes(&mut data1)?;
+        state2.randombytes(&mut data2)?;
+        assert_ne!(data1, data2);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "cref")]
     fn test_randombytes() {
+        use crate::link_c_reference::initialize_c_randombytes;
+        use crate::link_c_reference::randombytes as randombytes_c;
+
         let mut rng_state = AesState::with_increasing_seed();
         initialize_c_randombytes();
 
@@ -0,0 +1,3 @@
+//! Saber, the Saber variant in NIST security category 3
+
+crate::api::variant_api!(crate::saber_params::Saber);
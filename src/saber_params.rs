@@ -1,4 +1,4 @@
-use std::num::Wrapping;
+use core::num::Wrapping;
 
 #[allow(non_snake_case)]
 #[macro_export]
@@ -11,45 +11,11 @@ pub(crate) type U16 = Wrapping<u16>;
 
 /// degree of the polynomial ring
 pub(crate) const SABER_N: usize = 256;
-/// rank of the module
-pub(crate) const SABER_L: usize = if cfg!(SABER_L_IS_2) {
-    2
-} else if cfg!(SABER_L_IS_4) {
-    4
-} else if cfg!(SABER_L_IS_3) {
-    3
-} else {
-    0
-};
+/// largest rank of the module among all variants (FireSaber)
+pub(crate) const SABER_L_MAX: usize = 4;
+/// largest central binomial distribution parameter among all variants (LightSaber)
+pub(crate) const SABER_MU_MAX: usize = 10;
 
-#[cfg(test)]
-pub fn wrappedu162u16(plain_arr: &mut [u16], alias_arr: &[U16]) {
-    for (i, x) in alias_arr.iter().enumerate() {
-        plain_arr[i] = x.0;
-    }
-}
-const fn params() -> (usize, usize) {
-    let mut mu = 0usize;
-    let mut et = 0usize;
-
-    if SABER_L == 2 {
-        mu = 10;
-        et = 3;
-    } else if SABER_L == 3 {
-        mu = 8;
-        et = 4;
-    } else if SABER_L == 4 {
-        mu = 6;
-        et = 6;
-    }
-    (mu, et)
-}
-
-/// central binomial distribution parameter μ
-pub(crate) const SABER_MU: usize = params().0;
-
-/// rounding modulo ε_T
-pub(crate) const SABER_ET: usize = params().1;
 /// rounding modulo ε_Q
 pub(crate) const SABER_EQ: usize = 13;
 /// rounding modulo ε_P
@@ -60,22 +26,183 @@ pub(crate) const SABER_NOISE_SEEDBYTES: usize = 32;
 pub(crate) const SABER_KEYBYTES: usize = 32;
 pub(crate) const SABER_HASHBYTES: usize = 32;
 
-pub(crate) const SABER_POLYCOINBYTES: usize = SABER_MU * SABER_N / 8;
-
 pub(crate) const SABER_POLYBYTES: usize = SABER_EQ * SABER_N / 8;
-pub(crate) const SABER_POLYVECBYTES: usize = SABER_L * SABER_POLYBYTES;
-
 pub(crate) const SABER_POLYCOMPRESSEDBYTES: usize = SABER_EP * SABER_N / 8;
-pub(crate) const SABER_POLYVECCOMPRESSEDBYTES: usize = SABER_L * SABER_POLYCOMPRESSEDBYTES;
 
-pub(crate) const SABER_SCALEBYTES_KEM: usize = SABER_ET * SABER_N / 8;
+mod private {
+    pub trait Sealed {}
+}
+
+/// Parameter set of one Saber variant.
+///
+/// The variants only differ in the rank of the module `L`,
+/// the central binomial distribution parameter `MU` and the
+/// rounding modulo `ET`. All byte lengths are derived from these three.
+/// The trait is sealed; it is implemented by [`LightSaber`], [`Saber`] and [`FireSaber`].
+pub trait SaberParams: private::Sealed {
+    /// name of the variant as used by the NIST submission
+    const ALGNAME: &'static str;
+    /// object identifier of the variant (round 3, 256-bit shared secret) in the
+    /// Saber KEM arc `1.3.6.1.4.1.22554.5.3` of Bouncy Castle (`BCObjectIdentifiers.pqc_kem_saber`:
+    /// `lightsaberkem256r3`, `saberkem256r3` and `firesaberkem256r3`)
+    const OID: &'static str;
+    /// rank of the module
+    const L: usize;
+    /// central binomial distribution parameter μ
+    const MU: usize;
+    /// rounding modulo ε_T
+    const ET: usize;
+
+    const POLYCOINBYTES: usize = Self::MU * SABER_N / 8;
+    const POLYVECBYTES: usize = Self::L * SABER_POLYBYTES;
+    const POLYVECCOMPRESSEDBYTES: usize = Self::L * SABER_POLYCOMPRESSEDBYTES;
+    const SCALEBYTES_KEM: usize = Self::ET * SABER_N / 8;
 
-pub(crate) const SABER_INDCPA_PUBLICKEYBYTES: usize =
-    SABER_POLYVECCOMPRESSEDBYTES + SABER_SEEDBYTES;
-pub(crate) const SABER_INDCPA_SECRETKEYBYTES: usize = SABER_POLYVECBYTES;
+    const INDCPA_PUBLICKEYBYTES: usize = Self::POLYVECCOMPRESSEDBYTES + SABER_SEEDBYTES;
+    const INDCPA_SECRETKEYBYTES: usize = Self::POLYVECBYTES;
 
-pub(crate) const SABER_PUBLICKEYBYTES: usize = SABER_INDCPA_PUBLICKEYBYTES;
-pub(crate) const SABER_SECRETKEYBYTES: usize =
-    SABER_INDCPA_SECRETKEYBYTES + SABER_INDCPA_PUBLICKEYBYTES + SABER_HASHBYTES + SABER_KEYBYTES;
+    const PUBLICKEYBYTES: usize = Self::INDCPA_PUBLICKEYBYTES;
+    const SECRETKEYBYTES: usize = Self::INDCPA_SECRETKEYBYTES
+        + Self::INDCPA_PUBLICKEYBYTES
+        + SABER_HASHBYTES
+        + SABER_KEYBYTES;
+
+    const BYTES_CCA_DEC: usize = Self::POLYVECCOMPRESSEDBYTES + Self::SCALEBYTES_KEM;
+}
+
+/// LightSaber, the variant in NIST security category 1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LightSaber;
+
+/// Saber, the variant in NIST security category 3
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Saber;
+
+/// FireSaber, the variant in NIST security category 5
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FireSaber;
+
+/// largest ciphertext length among all variants (FireSaber)
+pub(crate) const SABER_BYTES_CCA_DEC_MAX: usize = FireSaber::BYTES_CCA_DEC;
+
+impl private::Sealed for LightSaber {}
+impl private::Sealed for Saber {}
+impl private::Sealed for FireSaber {}
+
+impl SaberParams for LightSaber {
+    const ALGNAME: &'static str = "LightSaber";
+    const OID: &'static str = "1.3.6.1.4.1.22554.5.3.7";
+    const L: usize = 2;
+    const MU: usize = 10;
+    const ET: usize = 3;
+}
+
+impl SaberParams for Saber {
+    const ALGNAME: &'static str = "Saber";
+    const OID: &'static str = "1.3.6.1.4.1.22554.5.3.8";
+    const L: usize = 3;
+    const MU: usize = 8;
+    const ET: usize = 4;
+}
+
+impl SaberParams for FireSaber {
+    const ALGNAME: &'static str = "FireSaber";
+    const OID: &'static str = "1.3.6.1.4.1.22554.5.3.9";
+    const L: usize = 4;
+    const MU: usize = 6;
+    const ET: usize = 6;
+}
+
+/// Saber variant selected at runtime, e.g. by a command-line option or by a key.
+///
+/// The parameter sets `LightSaber`, `Saber` and `FireSaber` select the variant
+/// at compile time; `Variant` provides their names and byte lengths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    LightSaber,
+    Saber,
+    FireSaber,
+}
 
-pub(crate) const SABER_BYTES_CCA_DEC: usize = SABER_POLYVECCOMPRESSEDBYTES + SABER_SCALEBYTES_KEM;
+/// Returns the constant `$name` of the parameter set of `$variant`
+macro_rules! variant_const {
+    ($variant:expr, $name:ident) => {
+        match $variant {
+            Variant::LightSaber => LightSaber::$name,
+            Variant::Saber => Saber::$name,
+            Variant::FireSaber => FireSaber::$name,
+        }
+    };
+}
+
+impl Variant {
+    /// All variants in increasing security category
+    pub const ALL: [Variant; 3] = [Variant::LightSaber, Variant::Saber, Variant::FireSaber];
+
+    /// Variant with the (case-insensitive) name `name`, e.g. `"lightsaber"`
+    pub fn from_name(name: &str) -> Option<Variant> {
+        Variant::ALL
+            .into_iter()
+            .find(|v| v.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Lowercase name, which is also the name of the module of the variant
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::LightSaber => "lightsaber",
+            Variant::Saber => "saber",
+            Variant::FireSaber => "firesaber",
+        }
+    }
+
+    /// Name as used by the NIST submission (`SaberParams::ALGNAME`)
+    pub fn algname(self) -> &'static str {
+        variant_const!(self, ALGNAME)
+    }
+
+    /// Object identifier (`SaberParams::OID`)
+    pub fn oid(self) -> &'static str {
+        variant_const!(self, OID)
+    }
+
+    /// Length of a public key in bytes
+    pub fn public_key_bytes(self) -> usize {
+        variant_const!(self, PUBLICKEYBYTES)
+    }
+
+    /// Length of a secret key in bytes
+    pub fn secret_key_bytes(self) -> usize {
+        variant_const!(self, SECRETKEYBYTES)
+    }
+
+    /// Length of a ciphertext in bytes
+    pub fn ciphertext_bytes(self) -> usize {
+        variant_const!(self, BYTES_CCA_DEC)
+    }
+}
+
+#[cfg(all(test, feature = "cref"))]
+pub fn wrappedu162u16(plain_arr: &mut [u16], alias_arr: &[U16]) {
+    for (i, x) in alias_arr.iter().enumerate() {
+        plain_arr[i] = x.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FireSaber, LightSaber, SaberParams, Variant};
+
+    #[test]
+    fn test_variant() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_name(variant.name()), Some(variant));
+            assert_eq!(Variant::from_name(variant.algname()), Some(variant));
+        }
+        assert_eq!(Variant::from_name("megasaber"), None);
+        assert_eq!(Variant::LightSaber.oid(), LightSaber::OID);
+        assert_eq!(
+            Variant::FireSaber.secret_key_bytes(),
+            FireSaber::SECRETKEYBYTES
+        );
+    }
+}
@@ -1,81 +1,168 @@
-/// Verify equivalence of bytestrings `a` and `b` in constant time.
-/// However, the length of arguments is not checked; only the smaller one is compared with.
-/// Returns 0 for equal strings, 1 for non-equal strings.
-pub(crate) fn verify(a: &[u8], b: &[u8]) -> u8 {
-    let mut r: u64 = 0;
-    let len = a.len().min(b.len());
-
-    for i in 0..len {
-        r |= (a[i] ^ b[i]) as u64;
+//! Constant-time comparison and selection of bytestrings.
+//!
+//! Both functions are built on `subtle`, whose `Choice` hides the
+//! condition behind an optimization barrier. Thus, the compiler cannot
+//! turn the masking into branches depending on secret data.
+
+use crate::Error;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// Fails with `Error::InvalidLength` unless `a` and `b` have the same length
+fn check_same_length(a: &[u8], b: &[u8]) -> Result<(), Error> {
+    if a.len() != b.len() {
+        return Err(Error::InvalidLength {
+            expected: a.len(),
+            got: b.len(),
+        });
     }
-    r = -(r as i64) as u64 >> 63;
-    r as u8
+    Ok(())
 }
 
-/// Move conditionally. If b = 1, then copy `x` to `r`. If b = 0, no operation.
-pub(crate) fn cmov(r: &mut [u8], x: &[u8], mut b: u8) {
-    b = -(b as i8) as u8;
+/// Verify equivalence of bytestrings `a` and `b` in constant time.
+/// Returns a `Choice` of 1 for equal and 0 for non-equal strings.
+/// Fails with `Error::InvalidLength` if the lengths differ.
+pub(crate) fn verify(a: &[u8], b: &[u8]) -> Result<Choice, Error> {
+    check_same_length(a, b)?;
+    Ok(a.ct_eq(b))
+}
 
-    for i in 0..x.len() {
-        r[i] ^= b & (x[i] ^ r[i]);
+/// Move conditionally in constant time. If `b` is 1, then copy `x` to `r`.
+/// If `b` is 0, no operation. Fails with `Error::InvalidLength` if the lengths differ.
+pub(crate) fn cmov(r: &mut [u8], x: &[u8], b: Choice) -> Result<(), Error> {
+    check_same_length(r, x)?;
+    for (r, x) in r.iter_mut().zip(x.iter()) {
+        r.conditional_assign(x, b);
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::link_c_reference::{cmov as cmov_c, verify as verify_c};
-    use crate::saber_params::{SABER_BYTES_CCA_DEC, SABER_KEYBYTES, SABER_SECRETKEYBYTES};
     use crate::verify::{cmov, verify};
+    use crate::Error;
+    use subtle::Choice;
 
     #[test]
-    fn test_verify() {
-        let mut a = [0u8; SABER_BYTES_CCA_DEC];
-        let mut b = [0u8; SABER_BYTES_CCA_DEC];
-        for i in 0..SABER_BYTES_CCA_DEC {
-            a[i] = b'a';
-            b[i] = b'a';
-        }
-        let len: usize = SABER_BYTES_CCA_DEC;
+    fn test_mismatched_lengths() {
+        assert_eq!(
+            verify(&[0u8; 4], &[0u8; 3]).err(),
+            Some(Error::InvalidLength {
+                expected: 4,
+                got: 3
+            })
+        );
+        assert_eq!(
+            cmov(&mut [0u8; 4], &[1u8; 5], Choice::from(1)),
+            Err(Error::InvalidLength {
+                expected: 4,
+                got: 5
+            })
+        );
+    }
 
-        let mut r1 = [0u8; 64];
-        let mut x = [0u8; SABER_SECRETKEYBYTES];
+    #[test]
+    fn test_cmov() -> Result<(), Error> {
+        let mut r = [b'r'; 8];
+        cmov(&mut r, &[b'x'; 8], Choice::from(0))?;
+        assert_eq!(r, [b'r'; 8]);
+        cmov(&mut r, &[b'x'; 8], Choice::from(1))?;
+        assert_eq!(r, [b'x'; 8]);
+        Ok(())
+    }
 
-        let mut res: u64;
-        unsafe { res = verify_c(&a, &b, len) };
+    #[cfg(feature = "cref")]
+    crate::link_c_reference::for_each_variant! {
+        use crate::verify::{cmov, verify};
+        use crate::Error;
 
-        unsafe { cmov_c(&mut r1, &x, SABER_KEYBYTES, res as u8) };
+        #[test]
+        fn test_verify() -> Result<(), Error> {
+            use cref::{cmov as cmov_c, verify as verify_c};
+            use cref::{SABER_BYTES_CCA_DEC, SABER_SECRETKEYBYTES};
+            use crate::saber_params::SABER_KEYBYTES;
 
-        let mut r2 = [0u8; 64];
+            let mut a = [0u8; SABER_BYTES_CCA_DEC];
+            let mut b = [0u8; SABER_BYTES_CCA_DEC];
+            for i in 0..SABER_BYTES_CCA_DEC {
+                a[i] = b'a';
+                b[i] = b'a';
+            }
+            let len: usize = SABER_BYTES_CCA_DEC;
 
-        for i in 0..SABER_KEYBYTES {
-            x[i] = b'a';
-            r1[i] = b'b';
-            r2[i] = b'b';
-        }
+            let mut r1 = [0u8; 64];
+            let mut x = [0u8; SABER_SECRETKEYBYTES];
 
-        let mut res2 = verify(&a, &b);
-        cmov(
-            &mut r2[0..SABER_KEYBYTES],
-            &x[0..SABER_KEYBYTES],
-            res2 as u8,
-        );
+            let mut res: u64;
+            unsafe { res = verify_c(&a, &b, len) };
 
-        assert_eq!(res as u8, res2);
-        assert_eq!(r1, r2);
+            unsafe { cmov_c(&mut r1, &x, SABER_KEYBYTES, res as u8) };
 
-        b[b.len() - 1] = b'b';
+            let mut r2 = [0u8; 64];
 
-        unsafe { res = verify_c(&a, &b, len) };
+            for i in 0..SABER_KEYBYTES {
+                x[i] = b'a';
+                r1[i] = b'b';
+                r2[i] = b'b';
+            }
 
-        unsafe { cmov_c(&mut r1, &x, SABER_KEYBYTES, res as u8) };
+            // the C reference returns 1 for non-equal strings
+            let mut res2 = verify(&a, &b)?;
+            cmov(&mut r2[0..SABER_KEYBYTES], &x[0..SABER_KEYBYTES], !res2)?;
 
-        res2 = verify(&a, &b);
-        cmov(
-            &mut r2[0..SABER_KEYBYTES],
-            &x[0..SABER_KEYBYTES],
-            res2 as u8,
-        );
-        assert_eq!(res as u8, res2);
-        assert_eq!(r1, r2);
+            assert_eq!(res as u8, (!res2).unwrap_u8());
+            assert_eq!(r1, r2);
+
+            b[b.len() - 1] = b'b';
+
+            unsafe { res = verify_c(&a, &b, len) };
+
+            unsafe { cmov_c(&mut r1, &x, SABER_KEYBYTES, res as u8) };
+
+            res2 = verify(&a, &b)?;
+            cmov(&mut r2[0..SABER_KEYBYTES], &x[0..SABER_KEYBYTES], !res2)?;
+            assert_eq!(res as u8, (!res2).unwrap_u8());
+            assert_eq!(r1, r2);
+            Ok(())
+        }
+    }
+}
+
+/// Kani proof harnesses, run with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use crate::verify::{cmov, verify};
+    use subtle::Choice;
+
+    /// Length of the bytestrings (the loops are unwound `LEN + 1` times)
+    const LEN: usize = 8;
+
+    #[kani::proof]
+    #[kani::unwind(9)]
+    fn verify_is_equality() {
+        let a: [u8; LEN] = kani::any();
+        let b: [u8; LEN] = kani::any();
+        // 1 for equal and 0 for non-equal strings
+        assert_eq!(bool::from(verify(&a, &b).unwrap()), a == b);
+    }
+
+    #[kani::proof]
+    #[kani::unwind(9)]
+    fn verify_rejects_mismatched_lengths() {
+        let a: [u8; LEN] = kani::any();
+        let len: usize = kani::any();
+        kani::assume(len <= LEN);
+        let b = &a[..len];
+        assert_eq!(verify(&a, b).is_err(), len != LEN);
+    }
+
+    #[kani::proof]
+    #[kani::unwind(9)]
+    fn cmov_copies_iff_set() {
+        let r: [u8; LEN] = kani::any();
+        let x: [u8; LEN] = kani::any();
+        let b: bool = kani::any();
+        let mut moved = r;
+        cmov(&mut moved, &x, Choice::from(u8::from(b))).unwrap();
+        assert_eq!(moved, if b { x } else { r });
     }
 }
@@ -0,0 +1,16 @@
+//! Wiping of intermediates holding secret data
+
+/// Overwrites the given places with zeros if feature `zeroize` is enabled.
+/// Does nothing otherwise. Accepts anything implementing `Zeroize`,
+/// e.g. integers, arrays and slices of them and `U16` values.
+macro_rules! wipe {
+    ($($x:expr),+ $(,)?) => {
+        #[cfg(feature = "zeroize")]
+        {
+            use zeroize::Zeroize;
+            $($x.zeroize();)+
+        }
+    };
+}
+
+pub(crate) use wipe;
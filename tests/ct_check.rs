@@ -0,0 +1,73 @@
+//! Constant-time check of the KEM with Valgrind memcheck (feature `ct-check`)
+//!
+//! Run natively, the test runs itself again under `valgrind`. There, the secret
+//! key, the coins and the RNG output are marked as undefined memory and memcheck
+//! fails the run if a branch or memory access depends on them.
+//! If `valgrind` is not installed, the test is skipped unless
+//! `RUSTY_SABER_REQUIRE_VALGRIND` is set (as in CI).
+
+use rusty_saber::ct_check::{declassify, running_on_valgrind};
+use rusty_saber::kem::{crypto_kem_dec, crypto_kem_enc, crypto_kem_keypair};
+use rusty_saber::rng::AesState;
+use rusty_saber::saber::CRYPTO_BYTES;
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams};
+use std::env;
+use std::io::ErrorKind;
+use std::process::Command;
+
+/// Set in the environment of the run under Valgrind
+const UNDER_VALGRIND: &str = "RUSTY_SABER_CT_CHECK";
+/// Fails the test instead of skipping it if `valgrind` is not installed
+const REQUIRE_VALGRIND: &str = "RUSTY_SABER_REQUIRE_VALGRIND";
+
+fn kem<P: SaberParams>(rng: &mut AesState) {
+    let mut pk = vec![0u8; P::PUBLICKEYBYTES];
+    let mut sk = vec![0u8; P::SECRETKEYBYTES];
+    let mut c = vec![0u8; P::BYTES_CCA_DEC];
+    let mut k_a = [0u8; CRYPTO_BYTES];
+    let mut k_b = [0u8; CRYPTO_BYTES];
+    let mut k_reject = [0u8; CRYPTO_BYTES];
+
+    crypto_kem_keypair::<P>(&mut pk, &mut sk, rng).expect("crypto_kem_keypair failed!");
+    crypto_kem_enc::<P>(&mut c, &mut k_a, &pk, rng).expect("crypto_kem_enc failed!");
+    crypto_kem_dec::<P>(&mut k_b, &c, &sk).expect("crypto_kem_dec failed!");
+    // implicit rejection
+    c[0] ^= 1;
+    crypto_kem_dec::<P>(&mut k_reject, &c, &sk).expect("crypto_kem_dec failed!");
+
+    declassify(&k_a);
+    declassify(&k_b);
+    declassify(&k_reject);
+    assert_eq!(k_a, k_b, "{}: shared secrets differ", P::ALGNAME);
+    assert_ne!(k_a, k_reject, "{}: ciphertext not rejected", P::ALGNAME);
+}
+
+fn main() {
+    if env::var_os(UNDER_VALGRIND).is_some() {
+        assert!(running_on_valgrind(), "client requests are not recognized");
+        let mut rng = AesState::with_increasing_seed();
+        kem::<LightSaber>(&mut rng);
+        kem::<Saber>(&mut rng);
+        kem::<FireSaber>(&mut rng);
+        return;
+    }
+
+    let exe = env::current_exe().unwrap();
+    let status = Command::new("valgrind")
+        .args(["--error-exitcode=1", "--track-origins=yes", "-q"])
+        .arg(exe)
+        .env(UNDER_VALGRIND, "1")
+        .status();
+    let status = match status {
+        Err(e) if e.kind() == ErrorKind::NotFound && env::var_os(REQUIRE_VALGRIND).is_none() => {
+            eprintln!("ct_check: skipped, valgrind is not installed");
+            return;
+        }
+        status => status.expect("cannot run valgrind"),
+    };
+    assert!(
+        status.success(),
+        "valgrind reported secret-dependent behaviour"
+    );
+    println!("ct_check: no secret-dependent branches or memory accesses found");
+}
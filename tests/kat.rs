@@ -0,0 +1,67 @@
+//! Regression tests regenerating the KAT files of all three variants.
+//!
+//! `kat/PQCkemKAT.sha256` lists the SHA-256 digests (in `sha256sum` format) of the
+//! `.req` and `.rsp` files generated by the C reference implementation of the
+//! NIST submission. The files are regenerated in memory and compared to them;
+//! no C toolchain is required.
+
+use rusty_saber::kat::{self, KAT_DEFAULT_COUNT};
+use rusty_saber::{FireSaber, LightSaber, Saber, SaberParams};
+use sha2::{Digest, Sha256};
+
+const DIGESTS: &str = include_str!("kat/PQCkemKAT.sha256");
+
+/// Expected hex digest of the file `name`
+fn expected_digest(name: &str) -> &'static str {
+    DIGESTS
+        .lines()
+        .find_map(|line| {
+            let (digest, file) = line.split_once("  ")?;
+            (file == name).then_some(digest)
+        })
+        .unwrap_or_else(|| panic!("no digest of {}", name))
+}
+
+fn digest(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn check_kat<P: SaberParams>() {
+    let requests = kat::generate_requests(KAT_DEFAULT_COUNT).unwrap();
+    let mut req = Vec::new();
+    kat::write_req(&mut req, &requests).unwrap();
+    let name = kat::file_name::<P>("req");
+    assert_eq!(digest(&req), expected_digest(&name), "{} differs", name);
+
+    let responses = requests
+        .iter()
+        .map(|request| kat::respond::<P>(request).unwrap())
+        .collect::<Vec<_>>();
+    let mut rsp = Vec::new();
+    kat::write_rsp::<P, _>(&mut rsp, &responses).unwrap();
+    let name = kat::file_name::<P>("rsp");
+    assert_eq!(digest(&rsp), expected_digest(&name), "{} differs", name);
+
+    // the regenerated file passes verification
+    let (algname, parsed) = kat::parse_rsp(&rsp[..]).unwrap();
+    assert_eq!(algname.as_deref(), Some(P::ALGNAME));
+    kat::verify::<P>(&parsed).unwrap();
+}
+
+#[test]
+fn test_kat_lightsaber() {
+    check_kat::<LightSaber>();
+}
+
+#[test]
+fn test_kat_saber() {
+    check_kat::<Saber>();
+}
+
+#[test]
+fn test_kat_firesaber() {
+    check_kat::<FireSaber>();
+}
@@ -0,0 +1,57 @@
+//! Property-based tests of the KEM of all three variants
+
+use proptest::prelude::*;
+use sha3::{Digest, Sha3_256};
+
+macro_rules! properties {
+    ($variant:ident) => {
+        mod $variant {
+            use super::*;
+            use rusty_saber::$variant::{self, Ciphertext, CRYPTO_CIPHERTEXTBYTES};
+
+            proptest! {
+                #![proptest_config(ProptestConfig::with_cases(64))]
+
+                #[test]
+                fn test_roundtrip(
+                    seed_a: [u8; 32],
+                    seed_s: [u8; 32],
+                    z: [u8; 32],
+                    m: [u8; 32],
+                ) {
+                    let (pk, sk) = $variant::keypair_derand(&seed_a, &seed_s, &z).unwrap();
+                    let (ct, ss) = $variant::encapsulate_derand(&pk, &m).unwrap();
+                    prop_assert_eq!(sk.decapsulate(&ct).unwrap(), ss);
+                    // z is stored at the end of the secret key
+                    prop_assert_eq!(&sk.as_bytes()[sk.as_bytes().len() - 32..], &z[..]);
+                }
+
+                #[test]
+                fn test_implicit_rejection(
+                    seed_a: [u8; 32],
+                    seed_s: [u8; 32],
+                    z: [u8; 32],
+                    m: [u8; 32],
+                    bit in 0..8 * CRYPTO_CIPHERTEXTBYTES,
+                ) {
+                    let (pk, sk) = $variant::keypair_derand(&seed_a, &seed_s, &z).unwrap();
+                    let (ct, ss) = $variant::encapsulate_derand(&pk, &m).unwrap();
+                    let mut tampered = *ct.as_bytes();
+                    tampered[bit / 8] ^= 1 << (bit % 8);
+                    let tampered = Ciphertext::from_bytes(tampered);
+
+                    // the shared secret is H(z || H(c)) for the tampered ciphertext c
+                    let mut kr = z.to_vec();
+                    kr.extend_from_slice(&Sha3_256::digest(tampered.as_bytes()));
+                    let rejected = sk.decapsulate(&tampered).unwrap();
+                    prop_assert_eq!(&rejected.as_bytes()[..], &Sha3_256::digest(&kr)[..]);
+                    prop_assert_ne!(rejected, ss);
+                }
+            }
+        }
+    };
+}
+
+properties!(lightsaber);
+properties!(saber);
+properties!(firesaber);